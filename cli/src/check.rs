@@ -0,0 +1,62 @@
+use crate::{for_each_input, load, Languages, StripArgs};
+use no_comment::{IntoWithoutComments as _, Position};
+use serde::Serialize;
+use std::error::Error;
+use std::path::PathBuf;
+
+/// A comment found in an input.
+#[derive(Serialize, Debug)]
+struct Finding {
+    path: PathBuf,
+    start: Position,
+    end: Position,
+    text: String,
+}
+
+/// Lists every comment in the inputs, as `file:line:column: first line of the comment` or as a
+/// JSON array with `--json`. Returns `Ok(false)` if any comment was found.
+pub fn run(args: &StripArgs, languages: &Languages) -> Result<bool, Box<dyn Error>> {
+    let inputs = args.input.inputs()?;
+
+    let mut findings = Vec::new();
+    let result = for_each_input(&inputs, "checked", |input| {
+        let loaded = match load(input, languages)? {
+            Some(loaded) => loaded,
+            None => return Ok(()),
+        };
+
+        for segment in loaded.text.chars().segments(loaded.comments) {
+            let segment = segment?;
+            if segment.is_comment() {
+                findings.push(Finding {
+                    path: input.path.clone(),
+                    start: segment.start,
+                    end: segment.end,
+                    text: segment.text,
+                });
+            }
+        }
+        Ok(())
+    });
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&findings)?);
+    } else {
+        for finding in &findings {
+            let first_line = finding
+                .text
+                .split(['\n', '\r', '\u{2028}', '\u{2029}'])
+                .next()
+                .unwrap_or_default();
+            println!(
+                "{}:{}: {}",
+                finding.path.display(),
+                finding.start,
+                first_line.trim_end()
+            );
+        }
+    }
+
+    result?;
+    Ok(findings.is_empty())
+}
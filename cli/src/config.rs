@@ -0,0 +1,83 @@
+use no_comment::registry::{Definition, Registry};
+use no_comment::validation::validate;
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Contents of a file of language definitions, `[[language]]` tables in TOML or
+/// `{"language": [...]}` in JSON.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct DefinitionsFile {
+    #[serde(default)]
+    language: Vec<Definition>,
+}
+
+/// The directory searched for language definitions: `$XDG_CONFIG_HOME/no-comment/languages`,
+/// falling back to `~/.config` (or `%APPDATA%` on Windows) if the variable isn't set.
+fn languages_dir() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| match cfg!(windows) {
+            true => env::var_os("APPDATA").map(PathBuf::from),
+            false => env::var_os("HOME").map(|home| Path::new(&home).join(".config")),
+        })?;
+    Some(config.join("no-comment").join("languages"))
+}
+
+/// Reads and validates the definitions of a file, as TOML unless its extension is `.json`.
+/// Errors name the file and, for invalid definitions, the offending entry.
+fn load(path: &Path) -> Result<Vec<Definition>, Box<dyn Error>> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let file: DefinitionsFile = match is_json {
+        true => serde_json::from_str(&text).map_err(|err| err.to_string()),
+        false => toml::from_str(&text).map_err(|err| err.to_string()),
+    }
+    .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    for (idx, definition) in file.language.iter().enumerate() {
+        validate(&definition.comments).map_err(|err| {
+            format!(
+                "{}: language[{}] (\"{}\"): {}",
+                path.display(),
+                idx,
+                definition.name,
+                err
+            )
+        })?;
+    }
+    Ok(file.language)
+}
+
+/// Builds the registry of languages: the built-in ones, then those defined in the files of the
+/// user's configuration directory (in alphabetical order), then those of `lang_files`. Later
+/// definitions take precedence.
+pub fn registry(lang_files: &[PathBuf]) -> Result<Registry, Box<dyn Error>> {
+    let mut files = Vec::new();
+    if let Some(dir) = languages_dir().filter(|dir| dir.is_dir()) {
+        let mut found = fs::read_dir(&dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        found.retain(|path| {
+            let ext = path.extension().and_then(|ext| ext.to_str());
+            path.is_file() && matches!(ext, Some("toml") | Some("json"))
+        });
+        found.sort();
+        files.extend(found);
+    }
+    files.extend(lang_files.iter().cloned());
+
+    let mut registry = Registry::new();
+    for path in &files {
+        for definition in load(path)? {
+            registry.register(definition);
+        }
+    }
+    Ok(registry)
+}
@@ -0,0 +1,111 @@
+use crate::Languages;
+use no_comment::diff::{self, Line};
+use no_comment::registry::Registry;
+use similar::udiff::UnifiedHunkHeader;
+use similar::{capture_diff_slices, group_diff_ops, Algorithm, DiffTag};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Args, Debug)]
+pub struct DiffArgs {
+    /// The original file
+    old: PathBuf,
+
+    /// The modified file
+    new: PathBuf,
+
+    /// Language of both files, detected from the new file if not given
+    #[arg(short, long, value_name = "LANG")]
+    lang: Option<String>,
+
+    /// Ignore differences in whitespace, including blank lines
+    #[arg(short = 'w', long)]
+    ignore_whitespace: bool,
+
+    /// Number of lines of context around each change
+    #[arg(short = 'U', long, value_name = "LINES", default_value_t = 3)]
+    context: usize,
+}
+
+/// Range of original line numbers spanned by `lines` in the file at `path`, such as `a.rs:3-5`,
+/// `None` if there are no lines.
+fn span(path: &Path, lines: &[Line]) -> Option<String> {
+    let first = lines.first()?.number;
+    let last = lines.last()?.number;
+    Some(match first == last {
+        true => format!("{}:{}", path.display(), first),
+        false => format!("{}:{}-{}", path.display(), first, last),
+    })
+}
+
+fn read(path: &Path) -> Result<String, Box<dyn Error>> {
+    fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err).into())
+}
+
+/// Prints a unified diff of the two files without their comments, which applies to the files
+/// once stripped. The section heading of each hunk gives the range of lines it covers in the
+/// original files. Returns `Ok(false)` if the files differ.
+pub fn run(args: &DiffArgs, registry: &Registry) -> Result<bool, Box<dyn Error>> {
+    let languages = Languages::new(registry, args.lang.as_deref())?;
+    let old_text = read(&args.old)?;
+    let new_text = read(&args.new)?;
+    let (_, comments) = languages.get(&args.new, &new_text).ok_or_else(|| {
+        format!(
+            "cannot detect the language of {}, use --lang",
+            args.new.display()
+        )
+    })?;
+
+    let options = diff::Options {
+        ignore_whitespace: args.ignore_whitespace,
+    };
+    let old = diff::lines(old_text.chars(), comments.clone(), &options)
+        .map_err(|err| format!("{}: {}", args.old.display(), err))?;
+    let new = diff::lines(new_text.chars(), comments, &options)
+        .map_err(|err| format!("{}: {}", args.new.display(), err))?;
+
+    let texts = |lines: &[Line]| lines.iter().map(|l| l.text.clone()).collect::<Vec<_>>();
+    let ops = capture_diff_slices(Algorithm::Myers, &texts(&old), &texts(&new));
+    let groups = group_diff_ops(ops, args.context);
+    if groups.is_empty() {
+        return Ok(true);
+    }
+
+    println!("--- {}", args.old.display());
+    println!("+++ {}", args.new.display());
+    for group in groups {
+        let first = &group[0];
+        let last = &group[group.len() - 1];
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+        // the hunk header counts stripped lines, its section heading maps them back to the
+        // lines of the original files
+        let spans = span(&args.old, &old[old_range])
+            .into_iter()
+            .chain(span(&args.new, &new[new_range]))
+            .collect::<Vec<_>>();
+        println!("{} {}", UnifiedHunkHeader::new(&group), spans.join(" "));
+
+        for op in group {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            match tag {
+                DiffTag::Equal => {
+                    for line in &new[new_range] {
+                        println!(" {}", line.text);
+                    }
+                }
+                DiffTag::Delete | DiffTag::Insert | DiffTag::Replace => {
+                    for line in &old[old_range] {
+                        println!("-{}", line.text);
+                    }
+                    for line in &new[new_range] {
+                        println!("+{}", line.text);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
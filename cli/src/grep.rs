@@ -0,0 +1,93 @@
+use crate::{for_each_input, load, InputArgs, Languages};
+use no_comment::grep::{scoped_lines, Scope};
+use no_comment::registry::Registry;
+use no_comment::Position;
+use regex::RegexBuilder;
+use serde::Serialize;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub struct GrepArgs {
+    /// Regular expression to search for, each line is searched separately
+    pattern: String,
+
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Search in comments instead of code
+    #[arg(long)]
+    comments: bool,
+
+    /// Treat the pattern as a plain string instead of a regular expression
+    #[arg(short = 'F', long)]
+    fixed_strings: bool,
+
+    /// Ignore case distinctions
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    /// Print machine-readable JSON instead of text
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct Match {
+    path: PathBuf,
+    position: Position,
+    text: String,
+    /// The original line, to print it as is
+    #[serde(skip)]
+    line: String,
+}
+
+/// Prints the matches of the pattern in the code (or the comments) of every input, as
+/// `file:line:column: line` or as JSON. Returns `Ok(false)` if nothing was found.
+pub fn run(args: &GrepArgs, registry: &Registry) -> Result<bool, Box<dyn Error>> {
+    let languages = Languages::new(registry, args.input.lang.as_deref())?;
+    let inputs = args.input.inputs()?;
+    let pattern = match args.fixed_strings {
+        true => regex::escape(&args.pattern),
+        false => args.pattern.clone(),
+    };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(args.ignore_case)
+        .build()?;
+    let scope = match args.comments {
+        true => Scope::Comments,
+        false => Scope::Code,
+    };
+
+    let mut matches = Vec::new();
+    let result = for_each_input(&inputs, "searched", |input| {
+        if let Some(loaded) = load(input, &languages)? {
+            for line in scoped_lines(loaded.text.chars(), loaded.comments, scope)? {
+                for found in regex.find_iter(&line.text).filter(|m| !m.is_empty()) {
+                    matches.push(Match {
+                        path: input.path.clone(),
+                        position: line.position(found.start()),
+                        text: found.as_str().to_string(),
+                        line: line.original.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    });
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&matches)?);
+    } else {
+        for found in &matches {
+            println!(
+                "{}:{}: {}",
+                found.path.display(),
+                found.position,
+                found.line
+            );
+        }
+    }
+
+    result.map(|()| !matches.is_empty())
+}
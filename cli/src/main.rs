@@ -0,0 +1,343 @@
+mod check;
+mod config;
+mod diff;
+mod grep;
+mod stats;
+mod todo;
+mod walk;
+
+use crate::walk::{Input, WalkOptions};
+use clap::{Parser, Subcommand};
+use no_comment::in_place::{self, strip_in_place};
+use no_comment::languages::UnknownLanguage;
+use no_comment::registry::{Definition, Registry};
+use no_comment::{Comment, IntoWithoutComments as _};
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Remove comments from files and whole source trees.
+///
+/// Files are written to standard output, directories are walked recursively (honouring
+/// `.gitignore`, `.ignore` and `.nocommentignore` files) and mirrored into the output directory.
+/// Binary files are skipped and files in an unknown language are copied unchanged. With
+/// `--in-place`, files and directories are rewritten instead.
+#[derive(Parser, Debug)]
+#[command(name = "no-comment", version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    strip: StripArgs,
+
+    /// Load extra language definitions from this TOML or JSON file, can be repeated. Files in
+    /// the `no-comment/languages` configuration directory are always loaded first.
+    #[arg(long = "lang-file", value_name = "FILE", global = true)]
+    lang_files: Vec<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Count code, comment, mixed and blank lines per file and per language
+    Stats(stats::StatsArgs),
+    /// List TODO, FIXME and similar annotations found in comments
+    Todo(todo::TodoArgs),
+    /// Compare two files without their comments, printing a unified diff of what remains
+    Diff(diff::DiffArgs),
+    /// Search for a pattern only in code, or only in comments
+    Grep(grep::GrepArgs),
+}
+
+/// Arguments selecting the inputs and their language, shared by all commands.
+#[derive(clap::Args, Debug)]
+struct InputArgs {
+    /// Files and directories to process, standard input is read if none are given
+    paths: Vec<PathBuf>,
+
+    /// Language of the input, detected from each file's name, shebang or modeline if not given
+    #[arg(short, long, value_name = "LANG")]
+    lang: Option<String>,
+
+    /// When walking directories, only process files matching this glob, can be repeated
+    #[arg(long = "include", value_name = "GLOB")]
+    includes: Vec<String>,
+
+    /// When walking directories, skip files matching this glob, can be repeated
+    #[arg(long = "exclude", value_name = "GLOB")]
+    excludes: Vec<String>,
+
+    /// Don't honour ignore files when walking directories
+    #[arg(long)]
+    no_ignore: bool,
+}
+
+impl InputArgs {
+    fn walk_options(&self, skip: Option<&Path>) -> WalkOptions {
+        WalkOptions {
+            includes: self.includes.clone(),
+            excludes: self.excludes.clone(),
+            no_ignore: self.no_ignore,
+            skip: skip.map(Path::to_path_buf),
+        }
+    }
+
+    /// Lists the inputs, see `walk::inputs`.
+    fn inputs(&self) -> Result<Vec<Input>, Box<dyn Error>> {
+        walk::inputs(&self.paths, &self.walk_options(None))
+    }
+}
+
+/// Arguments of the default command, removing (or checking for) comments.
+#[derive(clap::Args, Debug)]
+struct StripArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Directory to mirror processed directories into, required when a directory is given
+    #[arg(short, long, value_name = "DIR")]
+    output: Option<PathBuf>,
+
+    /// Rewrite files (and every file in directories) in place, atomically
+    #[arg(short, long, conflicts_with = "output")]
+    in_place: bool,
+
+    /// With --in-place, keep a copy of each original file with this suffix appended
+    #[arg(long, value_name = "SUFFIX", requires = "in_place")]
+    backup: Option<String>,
+
+    /// With --in-place, keep the access and modification times of the original files
+    #[arg(long, requires = "in_place")]
+    preserve_times: bool,
+
+    /// List every comment as file:line:column instead of removing them, exits with status 1 if
+    /// any comment is found
+    #[arg(long, conflicts_with_all = ["output", "in_place"])]
+    check: bool,
+
+    /// Print machine-readable JSON instead of text
+    #[arg(long, requires = "check")]
+    json: bool,
+}
+
+impl StripArgs {
+    fn in_place_options(&self) -> in_place::Options {
+        in_place::Options {
+            backup_suffix: self.backup.clone(),
+            preserve_times: self.preserve_times,
+        }
+    }
+}
+
+/// Resolves the language to use for each file, either the one forced with `--lang` or the
+/// detected one.
+struct Languages<'a> {
+    registry: &'a Registry,
+    forced: Option<&'a Definition>,
+}
+
+impl<'a> Languages<'a> {
+    fn new(registry: &'a Registry, lang: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let forced = match lang {
+            Some(name) => Some(
+                registry
+                    .get(name)
+                    .ok_or_else(|| UnknownLanguage(name.to_string()))?,
+            ),
+            None => None,
+        };
+        Ok(Self { registry, forced })
+    }
+
+    /// Returns the name and the comment specifications of the language of a file.
+    fn get(&self, path: &Path, text: &str) -> Option<(String, Box<[Comment]>)> {
+        self.forced
+            .or_else(|| self.registry.detect(path, text))
+            .map(|definition| (definition.name.clone(), definition.comments.clone()))
+    }
+}
+
+/// The contents of an input along with its language.
+struct Loaded {
+    text: String,
+    language: String,
+    comments: Box<[Comment]>,
+}
+
+/// Reads an input and resolves its language. Returns `None` for inputs that are skipped, that is,
+/// inputs found while walking directories that are binary or in an unknown language.
+fn load(input: &Input, languages: &Languages) -> Result<Option<Loaded>, Box<dyn Error>> {
+    let text = match walk::read_text(&input.path)? {
+        Some(text) => text,
+        None if input.explicit => return Err("binary file".into()),
+        None => return Ok(None),
+    };
+    match languages.get(&input.path, &text) {
+        Some((language, comments)) => Ok(Some(Loaded {
+            text,
+            language,
+            comments,
+        })),
+        None if input.explicit => Err("cannot detect the language, use --lang".into()),
+        None => Ok(None),
+    }
+}
+
+/// Runs `f` on every input, errors are reported for each input and processing continues with
+/// the next one. Returns an error if any input failed.
+fn for_each_input(
+    inputs: &[Input],
+    verb: &str,
+    mut f: impl FnMut(&Input) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let mut failed = 0;
+    for input in inputs {
+        if let Err(err) = f(input) {
+            eprintln!("no-comment: {}: {}", input.path.display(), err);
+            failed += 1;
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        1 => Err(format!("1 file could not be {}", verb).into()),
+        n => Err(format!("{} files could not be {}", n, verb).into()),
+    }
+}
+
+fn strip(text: &str, comments: Box<[Comment]>) -> Result<String, no_comment::Error> {
+    text.chars().without_comments(comments).try_collect()
+}
+
+/// Writes the stripped version of a single file (or standard input) to standard output.
+fn print_stripped(path: Option<&Path>, languages: &Languages) -> Result<(), Box<dyn Error>> {
+    let (name, text) = match path {
+        Some(path) => (
+            path,
+            fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?,
+        ),
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            (Path::new("<stdin>"), text)
+        }
+    };
+
+    let (_, comments) = languages.get(name, &text).ok_or_else(|| {
+        format!(
+            "cannot detect the language of {}, use --lang",
+            name.display()
+        )
+    })?;
+    let stripped = strip(&text, comments).map_err(|err| format!("{}: {}", name.display(), err))?;
+    io::stdout().write_all(stripped.as_bytes())?;
+    Ok(())
+}
+
+/// Mirrors `dir` into `output`, stripping comments from every file whose language is known.
+fn mirror(
+    dir: &Path,
+    output: &Path,
+    options: &WalkOptions,
+    languages: &Languages,
+) -> Result<(), Box<dyn Error>> {
+    for entry in walk::files(dir, options)? {
+        let text = match walk::read_text(&entry.path)? {
+            Some(text) => text,
+            None => {
+                eprintln!("skipping binary file {}", entry.path.display());
+                continue;
+            }
+        };
+
+        let out = output.join(&entry.relative);
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let result = match languages.get(&entry.path, &text) {
+            Some((_, comments)) => strip(&text, comments)
+                .map_err(|err| format!("{}: {}", entry.path.display(), err))?,
+            None => text,
+        };
+        fs::write(&out, result)
+            .map_err(|err| format!("cannot write {}: {}", out.display(), err))?;
+    }
+    Ok(())
+}
+
+/// Rewrites every given file and every file in the given directories in place.
+fn run_in_place(args: &StripArgs, languages: &Languages) -> Result<(), Box<dyn Error>> {
+    let inputs = args.input.inputs()?;
+    let options = args.in_place_options();
+
+    for_each_input(&inputs, "rewritten", |input| {
+        if let Some(loaded) = load(input, languages)? {
+            strip_in_place(&input.path, loaded.comments, &options)?;
+        }
+        Ok(())
+    })
+}
+
+/// Runs the default command, returns `Ok(false)` if the check requested with `--check` failed.
+fn run_strip(args: &StripArgs, registry: &Registry) -> Result<bool, Box<dyn Error>> {
+    let languages = Languages::new(registry, args.input.lang.as_deref())?;
+
+    if args.check {
+        return check::run(args, &languages);
+    }
+
+    if args.in_place {
+        if args.input.paths.is_empty() {
+            return Err("--in-place requires at least one file or directory".into());
+        }
+        return run_in_place(args, &languages).map(|()| true);
+    }
+
+    if args.input.paths.is_empty() {
+        return print_stripped(None, &languages).map(|()| true);
+    }
+
+    let options = args.input.walk_options(args.output.as_deref());
+    for path in &args.input.paths {
+        if path.is_dir() {
+            let output = args.output.as_ref().ok_or_else(|| {
+                format!(
+                    "{} is a directory, an output directory must be given with --output",
+                    path.display()
+                )
+            })?;
+            mirror(path, output, &options, &languages)?;
+        } else {
+            print_stripped(Some(path), &languages)?;
+        }
+    }
+    Ok(true)
+}
+
+/// Runs the command, returns `Ok(false)` if it ran successfully but found a problem, such as
+/// `--check` finding comments.
+fn run(cli: &Cli) -> Result<bool, Box<dyn Error>> {
+    let registry = config::registry(&cli.lang_files)?;
+    match &cli.command {
+        None => run_strip(&cli.strip, &registry),
+        Some(Command::Stats(args)) => stats::run(args, &registry),
+        Some(Command::Todo(args)) => todo::run(args, &registry),
+        Some(Command::Diff(args)) => diff::run(args, &registry),
+        Some(Command::Grep(args)) => grep::run(args, &registry),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match run(&cli) {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(err) => {
+            eprintln!("no-comment: {}", err);
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{for_each_input, load, InputArgs, Languages};
+use no_comment::registry::Registry;
+use no_comment::stats::LineStats;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Also list the counts of each file
+    #[arg(long)]
+    files: bool,
+
+    /// Print machine-readable JSON instead of a table
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct FileStats {
+    path: PathBuf,
+    language: String,
+    #[serde(flatten)]
+    lines: LineStats,
+}
+
+#[derive(Serialize, Debug)]
+struct LanguageStats {
+    language: String,
+    files: usize,
+    #[serde(flatten)]
+    lines: LineStats,
+}
+
+#[derive(Serialize, Debug)]
+struct Report {
+    files: Vec<FileStats>,
+    languages: Vec<LanguageStats>,
+    total: LanguageStats,
+}
+
+impl Report {
+    fn new(files: Vec<FileStats>) -> Self {
+        let mut by_language = BTreeMap::<&str, (usize, LineStats)>::new();
+        for file in &files {
+            let entry = by_language.entry(&file.language).or_default();
+            entry.0 += 1;
+            entry.1 += file.lines;
+        }
+
+        let languages = by_language
+            .into_iter()
+            .map(|(language, (files, lines))| LanguageStats {
+                language: language.to_string(),
+                files,
+                lines,
+            })
+            .collect();
+        let total = LanguageStats {
+            language: "Total".to_string(),
+            files: files.len(),
+            lines: files.iter().map(|file| file.lines).sum(),
+        };
+
+        Self {
+            files,
+            languages,
+            total,
+        }
+    }
+
+    /// Prints the report as aligned columns, the first column is the file name if `per_file`
+    /// is set and the language otherwise.
+    fn print_table(&self, per_file: bool) {
+        let header = [
+            if per_file { "File" } else { "Language" },
+            "Files",
+            "Code",
+            "Comment",
+            "Mixed",
+            "Blank",
+            "Total",
+        ];
+        let row = |name: String, files: usize, lines: &LineStats| {
+            let counts = [
+                files,
+                lines.code,
+                lines.comment,
+                lines.mixed,
+                lines.blank,
+                lines.total(),
+            ];
+            std::iter::once(name)
+                .chain(counts.iter().map(usize::to_string))
+                .collect::<Vec<_>>()
+        };
+
+        let mut rows = vec![header.iter().map(|s| s.to_string()).collect::<Vec<_>>()];
+        if per_file {
+            for file in &self.files {
+                rows.push(row(file.path.display().to_string(), 1, &file.lines));
+            }
+        } else {
+            for language in &self.languages {
+                rows.push(row(
+                    language.language.clone(),
+                    language.files,
+                    &language.lines,
+                ));
+            }
+        }
+        rows.push(row(
+            self.total.language.clone(),
+            self.total.files,
+            &self.total.lines,
+        ));
+
+        let mut widths = vec![0; header.len()];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for row in &rows {
+            let mut line = format!("{:<width$}", row[0], width = widths[0]);
+            for (cell, width) in row.iter().zip(&widths).skip(1) {
+                line.push_str(&format!("  {:>width$}", cell, width = width));
+            }
+            println!("{}", line);
+        }
+    }
+}
+
+/// Counts the lines of every input and prints them as a table (per language, or per file with
+/// `--files`) or as JSON.
+pub fn run(args: &StatsArgs, registry: &Registry) -> Result<bool, Box<dyn Error>> {
+    let languages = Languages::new(registry, args.input.lang.as_deref())?;
+    let inputs = args.input.inputs()?;
+
+    let mut files = Vec::new();
+    let result = for_each_input(&inputs, "counted", |input| {
+        if let Some(loaded) = load(input, &languages)? {
+            files.push(FileStats {
+                path: input.path.clone(),
+                language: loaded.language,
+                lines: LineStats::count(loaded.text.chars(), loaded.comments)?,
+            });
+        }
+        Ok(())
+    });
+
+    let report = Report::new(files);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        report.print_table(args.files);
+    }
+
+    result.map(|()| true)
+}
@@ -0,0 +1,76 @@
+use crate::{for_each_input, load, InputArgs, Languages};
+use no_comment::annotations::{annotations, Annotation, DEFAULT_TAGS};
+use no_comment::registry::Registry;
+use serde::Serialize;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(clap::Args, Debug)]
+pub struct TodoArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Tag to search for, can be repeated, defaults to TODO, FIXME, HACK and XXX
+    #[arg(short, long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+
+    /// Print machine-readable JSON instead of text
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct Finding {
+    path: PathBuf,
+    #[serde(flatten)]
+    annotation: Annotation,
+}
+
+/// Lists the annotations found in the comments of every input, as
+/// `file:line:column: TAG(owner) #issue: text` or as JSON.
+pub fn run(args: &TodoArgs, registry: &Registry) -> Result<bool, Box<dyn Error>> {
+    let languages = Languages::new(registry, args.input.lang.as_deref())?;
+    let inputs = args.input.inputs()?;
+    let tags = match args.tags.is_empty() {
+        true => DEFAULT_TAGS.to_vec(),
+        false => args.tags.iter().map(String::as_str).collect(),
+    };
+
+    let mut findings = Vec::new();
+    let result = for_each_input(&inputs, "searched", |input| {
+        if let Some(loaded) = load(input, &languages)? {
+            for annotation in annotations(loaded.text.chars(), loaded.comments, &tags)? {
+                findings.push(Finding {
+                    path: input.path.clone(),
+                    annotation,
+                });
+            }
+        }
+        Ok(())
+    });
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&findings)?);
+    } else {
+        for Finding { path, annotation } in &findings {
+            let mut line = format!(
+                "{}:{}: {}",
+                path.display(),
+                annotation.position,
+                annotation.tag
+            );
+            if let Some(owner) = &annotation.owner {
+                line.push_str(&format!("({})", owner));
+            }
+            if let Some(issue) = &annotation.issue {
+                line.push_str(&format!(" #{}", issue));
+            }
+            if !annotation.text.is_empty() {
+                line.push_str(&format!(": {}", annotation.text));
+            }
+            println!("{}", line);
+        }
+    }
+
+    result.map(|()| true)
+}
@@ -0,0 +1,151 @@
+use globset::{Glob, GlobSetBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read as _};
+use std::path::{Path, PathBuf};
+
+/// Number of bytes at the beginning of a file that are checked for NUL bytes when deciding
+/// whether a file is binary, this is the same heuristic as git's.
+const BINARY_CHECK_LEN: usize = 8000;
+
+/// Name of the ignore file specific to this tool, it uses the same syntax as `.gitignore`.
+const IGNORE_FILE_NAME: &str = ".nocommentignore";
+
+/// Options controlling which files are visited when walking a directory.
+#[derive(Debug, Default)]
+pub struct WalkOptions {
+    /// Only files matching at least one of these globs are visited (all files if empty)
+    pub includes: Vec<String>,
+    /// Files matching any of these globs are not visited
+    pub excludes: Vec<String>,
+    /// Whether to ignore `.gitignore`, `.ignore` and `.nocommentignore` files
+    pub no_ignore: bool,
+    /// Directory that is never entered, used to avoid walking into the output directory
+    pub skip: Option<PathBuf>,
+}
+
+/// A file to process, either given on the command line or found while walking a directory.
+#[derive(Debug)]
+pub struct Input {
+    /// Path of the file, `-` stands for standard input
+    pub path: PathBuf,
+    /// Whether the file was given on the command line, if not, it is silently skipped when it
+    /// is binary or in an unknown language
+    pub explicit: bool,
+}
+
+/// Lists the files given on the command line and the files in the directories given on the
+/// command line. If no paths are given, standard input is the only input.
+pub fn inputs(paths: &[PathBuf], options: &WalkOptions) -> Result<Vec<Input>, Box<dyn Error>> {
+    if paths.is_empty() {
+        return Ok(vec![Input {
+            path: PathBuf::from("-"),
+            explicit: true,
+        }]);
+    }
+
+    let mut inputs = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            inputs.extend(files(path, options)?.into_iter().map(|entry| Input {
+                path: entry.path,
+                explicit: false,
+            }));
+        } else {
+            inputs.push(Input {
+                path: path.clone(),
+                explicit: true,
+            });
+        }
+    }
+    Ok(inputs)
+}
+
+/// A file found while walking a directory.
+#[derive(Debug)]
+pub struct Entry {
+    /// Path of the file, starting with the walked directory
+    pub path: PathBuf,
+    /// Path of the file relative to the walked directory
+    pub relative: PathBuf,
+}
+
+/// Recursively lists the files in `dir` that aren't ignored according to `options`, in a stable
+/// (sorted) order. Hidden files and directories are skipped.
+pub fn files(dir: &Path, options: &WalkOptions) -> Result<Vec<Entry>, Box<dyn Error>> {
+    // includes are matched after the walk rather than added as overrides, overrides whitelist
+    // files, which would take precedence over ignore files and the hidden file filter
+    let mut includes = GlobSetBuilder::new();
+    for glob in &options.includes {
+        includes.add(Glob::new(glob)?);
+    }
+    let includes = includes.build()?;
+
+    let mut overrides = OverrideBuilder::new(dir);
+    for glob in &options.excludes {
+        overrides.add(&format!("!{}", glob))?;
+    }
+
+    let skip = match &options.skip {
+        Some(skip) => fs::canonicalize(skip).ok(),
+        None => None,
+    };
+
+    let mut walk = WalkBuilder::new(dir);
+    walk.overrides(overrides.build()?)
+        .git_ignore(!options.no_ignore)
+        .git_global(!options.no_ignore)
+        .git_exclude(!options.no_ignore)
+        .ignore(!options.no_ignore)
+        .parents(!options.no_ignore)
+        .require_git(false);
+    if !options.no_ignore {
+        walk.add_custom_ignore_filename(IGNORE_FILE_NAME);
+    }
+    let walk = walk
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |entry| match (&skip, fs::canonicalize(entry.path())) {
+            (Some(skip), Ok(path)) => &path != skip,
+            _ => true,
+        })
+        .build();
+
+    let mut entries = Vec::new();
+    for entry in walk {
+        let entry = entry?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.into_path();
+        let relative = path.strip_prefix(dir)?.to_path_buf();
+        // like in `.gitignore`, a glob may match either the whole relative path or the file name
+        let included = includes.is_empty()
+            || includes.is_match(&relative)
+            || relative
+                .file_name()
+                .is_some_and(|name| includes.is_match(name));
+        if !included {
+            continue;
+        }
+        entries.push(Entry { path, relative });
+    }
+    Ok(entries)
+}
+
+/// Reads a file (or standard input if `path` is `-`) as text, returns `None` if the file is
+/// binary, that is, if it contains a NUL byte near the beginning or if it isn't valid UTF-8.
+pub fn read_text(path: &Path) -> io::Result<Option<String>> {
+    let bytes = if path == Path::new("-") {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        bytes
+    } else {
+        fs::read(path)?
+    };
+    if bytes.iter().take(BINARY_CHECK_LEN).any(|&b| b == 0) {
+        return Ok(None);
+    }
+    Ok(String::from_utf8(bytes).ok())
+}
@@ -0,0 +1,557 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn no_comment(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_no-comment"))
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", dir.join(".config"))
+        .output()
+        .unwrap()
+}
+
+fn write(dir: &Path, path: &str, contents: impl AsRef<[u8]>) {
+    let path = dir.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+fn read(dir: &Path, path: &str) -> String {
+    fs::read_to_string(dir.join(path)).unwrap()
+}
+
+#[test]
+fn test_single_file() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "main.rs", "fn main() {} // entry\n");
+
+    let output = no_comment(&["main.rs"], tmp.path());
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "fn main() {} \n");
+}
+
+#[test]
+fn test_unknown_language() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "notes", "a // b\n");
+
+    let output = no_comment(&["notes"], tmp.path());
+    assert!(!output.status.success());
+
+    let output = no_comment(&["--lang", "c++", "notes"], tmp.path());
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "a \n");
+}
+
+#[test]
+fn test_directory_requires_output() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "src/main.rs", "fn main() {}\n");
+
+    let output = no_comment(&["src"], tmp.path());
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_mirror() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "src/main.rs", "fn main() {} /* entry */\n");
+    write(dir, "src/util/mod.py", "x = 1 # one\n");
+    write(dir, "src/README", "# not a comment\n");
+    write(dir, "src/logo.png", b"\x89PNG\r\n\x1a\n\0\0\0");
+    write(dir, "src/generated.rs", "// generated\n");
+    write(dir, "src/.gitignore", "generated.rs\n");
+
+    let output = no_comment(&["src", "--output", "out"], dir);
+    assert!(output.status.success(), "{:?}", output);
+
+    assert_eq!(read(dir, "out/main.rs"), "fn main() {} \n");
+    assert_eq!(read(dir, "out/util/mod.py"), "x = 1 \n");
+    assert_eq!(read(dir, "out/README"), "# not a comment\n");
+    assert!(!dir.join("out/logo.png").exists());
+    assert!(!dir.join("out/generated.rs").exists());
+    assert!(!dir.join("out/.gitignore").exists());
+
+    let output = no_comment(&["src", "--output", "all", "--no-ignore"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(read(dir, "all/generated.rs"), "\n");
+}
+
+#[test]
+fn test_mirror_nocommentignore() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "src/a.rs", "a // a\n");
+    write(dir, "src/b.rs", "b // b\n");
+    write(dir, "src/.nocommentignore", "b.rs\n");
+
+    let output = no_comment(&["src", "--output", "out"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(read(dir, "out/a.rs"), "a \n");
+    assert!(!dir.join("out/b.rs").exists());
+
+    let output = no_comment(&["src", "--output", "all", "--no-ignore"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(read(dir, "all/a.rs"), "a \n");
+    assert_eq!(read(dir, "all/b.rs"), "b \n");
+}
+
+#[test]
+fn test_mirror_globs() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "src/a.rs", "a // a\n");
+    write(dir, "src/b.rs", "b // b\n");
+    write(dir, "src/c.py", "c # c\n");
+    // an include doesn't override ignore files or the hidden file filter
+    write(dir, "src/.gitignore", "gen.rs\n");
+    write(dir, "src/gen.rs", "g // g\n");
+    write(dir, "src/.hidden.rs", "h // h\n");
+
+    let args = ["src", "-o", "out", "--include", "*.rs", "--exclude", "b.*"];
+    let output = no_comment(&args, dir);
+    assert!(output.status.success(), "{:?}", output);
+
+    assert_eq!(read(dir, "out/a.rs"), "a \n");
+    assert!(!dir.join("out/b.rs").exists());
+    assert!(!dir.join("out/c.py").exists());
+    assert!(!dir.join("out/gen.rs").exists());
+    assert!(!dir.join("out/.hidden.rs").exists());
+}
+
+#[test]
+fn test_mirror_into_itself() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "a.rs", "a // a\n");
+
+    let output = no_comment(&[".", "-o", "out"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(read(dir, "out/a.rs"), "a \n");
+
+    // running again must not pick up the previous output
+    let output = no_comment(&[".", "-o", "out"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(!dir.join("out/out").exists());
+}
+
+#[test]
+fn test_stray_close_pattern() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "main.c", "int x; */\n");
+
+    let output = no_comment(&["main.c"], tmp.path());
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("main.c: Got \"*/\" without matching \"/*\" at 1:8"));
+}
+
+#[test]
+fn test_in_place() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "main.rs", "fn main() {} // entry\n");
+    write(dir, "src/lib.rs", "/* lib */ pub fn f() {}\n");
+    write(dir, "src/notes", "// kept\n");
+    write(dir, "src/bad.c", "int x; */\n");
+
+    let args = ["--in-place", "--backup", ".orig", "main.rs", "src"];
+    let output = no_comment(&args, dir);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("bad.c"), "{}", stderr);
+
+    assert_eq!(read(dir, "main.rs"), "fn main() {} \n");
+    assert_eq!(read(dir, "main.rs.orig"), "fn main() {} // entry\n");
+    assert_eq!(read(dir, "src/lib.rs"), " pub fn f() {}\n");
+    assert_eq!(read(dir, "src/notes"), "// kept\n");
+    assert_eq!(read(dir, "src/bad.c"), "int x; */\n");
+    assert!(!dir.join("src/bad.c.orig").exists());
+}
+
+#[test]
+fn test_in_place_crlf() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "main.rs", "fn main() {} // entry\r\n/* a\r\n */\r\n");
+    write(dir, "script.py", "# header\r\nx = 1  # one\r\n");
+
+    let output = no_comment(&["--in-place", "main.rs", "script.py"], dir);
+    assert!(output.status.success());
+    assert_eq!(read(dir, "main.rs"), "fn main() {} \r\n\r\n");
+    assert_eq!(read(dir, "script.py"), "\r\nx = 1  \r\n");
+}
+
+#[test]
+fn test_in_place_conflicts() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "main.rs", "\n");
+
+    let output = no_comment(&["--in-place", "main.rs", "-o", "out"], tmp.path());
+    assert!(!output.status.success());
+
+    let output = no_comment(&["--backup", ".orig", "main.rs"], tmp.path());
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_check() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "src/clean.rs", "fn main() {}\n");
+    write(dir, "src/dirty.rs", "fn f() {} // one\n/* two\nlines */\n");
+
+    let output = no_comment(&["--check", "src/clean.rs"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stdout.is_empty());
+
+    let output = no_comment(&["--check", "src"], dir);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let expected = format!(
+        "{0}:1:11: // one\n{0}:2:1: /* two\n",
+        Path::new("src").join("dirty.rs").display()
+    );
+    assert_eq!(stdout, expected);
+}
+
+#[test]
+fn test_check_json() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "a.py", "x = 1 # one\n");
+
+    let output = no_comment(&["--check", "--json", "a.py"], tmp.path());
+    assert_eq!(output.status.code(), Some(1));
+    let findings: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        findings,
+        serde_json::json!([{
+            "path": "a.py",
+            "start": { "offset": 6, "line": 1, "column": 7 },
+            "end": { "offset": 11, "line": 1, "column": 12 },
+            "text": "# one",
+        }])
+    );
+}
+
+#[test]
+fn test_check_error() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "a.c", "int x; */\n");
+
+    let output = no_comment(&["--check", "a.c"], tmp.path());
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_stats() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "src/a.rs", "// a\nfn a() {}\n\nfn b() {} // b\n");
+    write(dir, "src/b.rs", "/*\n * b\n */\nfn b() {}\n");
+    write(dir, "src/c.py", "x = 1\n");
+
+    let output = no_comment(&["stats", "src"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        "\
+Language  Files  Code  Comment  Mixed  Blank  Total
+Python        1     1        0      0      0      1
+Rust          2     2        4      1      1      8
+Total         3     3        4      1      1      9
+"
+    );
+}
+
+#[test]
+fn test_stats_json() {
+    let tmp = TempDir::new().unwrap();
+    write(tmp.path(), "a.hs", "-- a\nmain = pure ()\n");
+
+    let output = no_comment(&["stats", "--json", "a.hs"], tmp.path());
+    assert!(output.status.success(), "{:?}", output);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let counts = serde_json::json!({
+        "files": 1, "code": 1, "comment": 1, "mixed": 0, "blank": 0,
+    });
+    assert_eq!(report["languages"][0]["language"], "Haskell");
+    assert_eq!(report["files"][0]["path"], "a.hs");
+    assert_eq!(report["files"][0]["comment"], 1);
+    for (key, value) in counts.as_object().unwrap() {
+        assert_eq!(&report["total"][key], value, "wrong {}", key);
+    }
+}
+
+#[test]
+fn test_todo() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(
+        dir,
+        "src/main.rs",
+        "let s = \"TODO: no\"; // TODO(alice): #12 yes\n/* FIXME */\n// NOTE: custom\n",
+    );
+
+    let output = no_comment(&["todo", "src"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let path = Path::new("src").join("main.rs");
+    let expected = format!(
+        "{0}:1:24: TODO(alice) #12: yes\n{0}:2:4: FIXME\n",
+        path.display()
+    );
+    assert_eq!(stdout, expected);
+
+    let output = no_comment(&["todo", "--tag", "NOTE", "--json", "src"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    let findings: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(findings.as_array().unwrap().len(), 1);
+    assert_eq!(findings[0]["tag"], "NOTE");
+    assert_eq!(findings[0]["text"], "custom");
+    assert_eq!(findings[0]["position"]["line"], 3);
+}
+
+#[test]
+fn test_diff() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(
+        dir,
+        "old.rs",
+        "// header\nfn a() {}\nfn b() {}\nfn c() {}\n",
+    );
+    write(
+        dir,
+        "same.rs",
+        "/* new\n header */\nfn a() {}\nfn b() {} // b\nfn c() {}\n",
+    );
+    write(
+        dir,
+        "new.rs",
+        "/* new\n header */\nfn a() {}\nfn bb() {}\nfn c() {}\n",
+    );
+
+    let output = no_comment(&["diff", "old.rs", "same.rs"], dir);
+    assert!(!output.status.success(), "{:?}", output);
+
+    let output = no_comment(&["diff", "-w", "old.rs", "same.rs"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stdout.is_empty());
+
+    let output = no_comment(&["diff", "-w", "-U", "1", "old.rs", "new.rs"], dir);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        "\
+--- old.rs
++++ new.rs
+@@ -1,3 +1,3 @@ old.rs:2-4 new.rs:3-5
+ fn a() {}
+-fn b() {}
++fn bb() {}
+ fn c() {}
+"
+    );
+}
+
+#[test]
+fn test_grep() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(
+        dir,
+        "src/main.rs",
+        "fn main() {\n    // old_api();\n    new_api(); /* old_api() */ Old_Api();\n}\n",
+    );
+    let path = Path::new("src").join("main.rs");
+
+    let output = no_comment(&["grep", "-i", "old_api", "src"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let expected = format!(
+        "{}:3:32:     new_api(); /* old_api() */ Old_Api();\n",
+        path.display()
+    );
+    assert_eq!(stdout, expected);
+
+    let output = no_comment(
+        &["grep", "--comments", "--json", "-F", "old_api()", "src"],
+        dir,
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let matches: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let lines = matches
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["position"]["line"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(lines, [2, 3]);
+    assert_eq!(matches[1]["position"]["column"], 19);
+
+    let output = no_comment(&["grep", "missing", "src"], dir);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_grep_line_terminators() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "cr.c", "a // x\rfoo();\n");
+    write(dir, "ls.c", "a // x\u{2028}foo(); // y\u{2029}\n");
+
+    let output = no_comment(&["grep", "foo", "cr.c"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "cr.c:2:1: foo();\n"
+    );
+
+    let output = no_comment(&["grep", "foo", "ls.c"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "ls.c:2:1: foo(); // y\n"
+    );
+}
+
+#[test]
+fn test_lang_files() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(
+        dir,
+        ".config/no-comment/languages/lisp.toml",
+        r##"
+[[language]]
+name = "Lisp"
+aliases = ["scheme"]
+extensions = ["lisp"]
+comments = [
+    { open_pat = ";", close_pat = "\n", keep_close_pat = true, allow_close_pat = true },
+    { open_pat = "#|", close_pat = "|#", nests = true },
+    { open_pat = '"', close_pat = '"', literal = true, escape = "\\" },
+]
+"##,
+    );
+    write(
+        dir,
+        "ini.json",
+        r#"{"language": [{"name": "INI", "extensions": ["ini"], "comments": [
+            {"open_pat": ";", "close_pat": "\n", "keep_close_pat": true, "allow_close_pat": true}
+        ]}]}"#,
+    );
+    write(
+        dir,
+        "a.lisp",
+        "(f \"; \\\" x\") ; call\n#| a #| b |# |#(g)\n",
+    );
+    write(dir, "a.ini", "[a] ; section\n");
+
+    let output = no_comment(&["a.lisp"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "(f \"; \\\" x\") \n(g)\n"
+    );
+
+    let output = no_comment(&["a.ini"], dir);
+    assert_eq!(output.status.code(), Some(2));
+    let output = no_comment(&["--lang-file", "ini.json", "a.ini"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "[a] \n");
+
+    let output = no_comment(&["stats", "--lang-file", "ini.json", "--json", "."], dir);
+    assert!(output.status.success(), "{:?}", output);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let languages = report["languages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|language| language["language"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(languages, ["INI", "JSON", "Lisp"]);
+}
+
+#[test]
+fn test_lang_file_regex() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(
+        dir,
+        "asm.toml",
+        r##"
+[[language]]
+name = "Assembler"
+extensions = ["s"]
+comments = [
+    { open_pat = ";", close_pat = "\n", keep_close_pat = true, allow_close_pat = true },
+    { open_pats = [], open_regex = '\.L[0-9]+:', close_pat = "\n", keep_close_pat = true, allow_close_pat = true, open_anchor = "line_start" },
+]
+"##,
+    );
+    write(dir, "a.s", ".L1: debug\nmov r1, .L1 ; load\n");
+    write(
+        dir,
+        "bad.toml",
+        "[[language]]\nname = \"A\"\ncomments = [{ open_pat = \"#\", close_pat = \"\\n\", open_regex = \"(\" }]\n",
+    );
+
+    let output = no_comment(&["--lang-file", "asm.toml", "a.s"], dir);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "\nmov r1, .L1 \n"
+    );
+
+    let output = no_comment(&["--lang-file", "bad.toml", "a.s"], dir);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("bad.toml") && stderr.contains("invalid regular expression"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_lang_file_errors() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write(dir, "a.c", "int x;\n");
+    write(
+        dir,
+        "bad.toml",
+        "[[language]]\nname = \"A\"\ncomments = [{ open_pat = \"#\", close_pat = \"\\n\" }]\n\n[[language]]\nname = \"B\"\ncomments = [{ open_pat = \"\", close_pat = \"x\" }]\n",
+    );
+    write(
+        dir,
+        "typo.toml",
+        "[[language]]\nname = \"A\"\ncomments = [{ open = \"#\" }]\n",
+    );
+
+    let output = no_comment(&["--lang-file", "bad.toml", "a.c"], dir);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("bad.toml: language[1] (\"B\"): comments[0]: the open pattern is empty"),
+        "{}",
+        stderr
+    );
+
+    let output = no_comment(&["--lang-file", "typo.toml", "a.c"], dir);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("typo.toml") && stderr.contains("open"),
+        "{}",
+        stderr
+    );
+}
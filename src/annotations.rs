@@ -0,0 +1,163 @@
+use crate::error::Error;
+use crate::position::{ends_line, is_line_terminator, Position};
+use crate::without_comments::{Comment, IntoWithoutComments as _};
+use std::borrow::Cow;
+
+/// The tags searched for by default.
+pub const DEFAULT_TAGS: [&str; 4] = ["TODO", "FIXME", "HACK", "XXX"];
+
+/// An annotation such as `TODO(alice): #123 handle errors` found in a comment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Annotation {
+    /// The tag, such as `TODO`
+    pub tag: String,
+    /// The owner given in parentheses right after the tag, such as `alice`
+    pub owner: Option<String>,
+    /// The issue number given as `#123` at the beginning of the text, without the `#`
+    pub issue: Option<String>,
+    /// The rest of the line, without the comment's close pattern
+    pub text: String,
+    /// Position of the first `char` of the tag
+    pub position: Position,
+}
+
+/// Finds the annotations tagged with one of `tags` in the comments of `text`, text outside of
+/// comments is never searched. Tags are case-sensitive and must be whole words, at most one
+/// annotation is found per line.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::annotations::{annotations, DEFAULT_TAGS};
+/// use no_comment::languages;
+///
+/// let text = "let todo = \"TODO\"; // FIXME(bob): #42 rename\n";
+/// let found = annotations(text.chars(), languages::rust(), &DEFAULT_TAGS).unwrap();
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].tag, "FIXME");
+/// assert_eq!(found[0].owner.as_deref(), Some("bob"));
+/// assert_eq!(found[0].issue.as_deref(), Some("42"));
+/// assert_eq!(found[0].text, "rename");
+/// assert_eq!(found[0].position.column, 23);
+/// ```
+pub fn annotations(
+    text: impl Iterator<Item = char>,
+    language: Box<[Comment]>,
+    tags: &[&str],
+) -> Result<Vec<Annotation>, Error> {
+    let close_pats = language
+        .iter()
+        .flat_map(|comment| comment.close_pats.iter().cloned())
+        .filter(|close_pat| !close_pat.trim().is_empty())
+        .collect::<Vec<_>>();
+
+    let mut found = Vec::new();
+    for segment in text.segments(language) {
+        let segment = segment?;
+        if !segment.is_comment() {
+            continue;
+        }
+
+        // the byte index and position of the start of the current line, and the position of `c`
+        let (mut start, mut line_start, mut position) = (0, segment.start, segment.start);
+        let mut prev = None;
+        for (idx, c) in segment.text.char_indices() {
+            if ends_line(prev, c) {
+                found.extend(parse_line(
+                    &segment.text[start..idx],
+                    line_start,
+                    tags,
+                    &close_pats,
+                ));
+            }
+            position.advance(prev, c);
+            if is_line_terminator(c) {
+                start = idx + c.len_utf8();
+                line_start = position;
+            }
+            prev = Some(c);
+        }
+        found.extend(parse_line(
+            &segment.text[start..],
+            line_start,
+            tags,
+            &close_pats,
+        ));
+    }
+    Ok(found)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Skips leading whitespace and at most one colon.
+fn skip_colon(s: &str) -> &str {
+    let s = s.trim_start();
+    match s.starts_with(':') {
+        true => s[1..].trim_start(),
+        false => s,
+    }
+}
+
+/// Finds the first tag in `line` and parses the annotation following it.
+fn parse_line(
+    line: &str,
+    line_start: Position,
+    tags: &[&str],
+    close_pats: &[Cow<'static, str>],
+) -> Option<Annotation> {
+    let (idx, tag) = line
+        .char_indices()
+        .filter(|&(idx, _)| !line[..idx].ends_with(is_word_char))
+        .find_map(|(idx, _)| {
+            tags.iter()
+                .find(|tag| {
+                    line[idx..].starts_with(*tag)
+                        && !line[idx + tag.len()..].starts_with(is_word_char)
+                })
+                .map(|tag| (idx, *tag))
+        })?;
+
+    let mut rest = &line[idx + tag.len()..];
+
+    let mut owner = None;
+    if rest.starts_with('(') {
+        if let Some(end) = rest.find(')') {
+            owner = Some(rest[1..end].trim().to_string());
+            rest = &rest[end + 1..];
+        }
+    }
+
+    // the issue can come before or after the colon: `TODO: #1 text` or `TODO #1: text`
+    rest = skip_colon(rest);
+    let mut issue = None;
+    if rest.starts_with('#') {
+        let digits = rest[1..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - 1);
+        if digits > 0 {
+            issue = Some(rest[1..=digits].to_string());
+            rest = skip_colon(&rest[1 + digits..]);
+        }
+    }
+
+    let mut text = rest.trim_end();
+    for close_pat in close_pats {
+        if text.ends_with(close_pat.as_ref()) {
+            text = text[..text.len() - close_pat.len()].trim_end();
+        }
+    }
+
+    let mut position = line_start;
+    position.advance_str(&line[..idx]);
+
+    Some(Annotation {
+        tag: tag.to_string(),
+        owner,
+        issue,
+        text: text.to_string(),
+        position,
+    })
+}
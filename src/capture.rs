@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+
+/// Default maximum length in `char`s of a captured part, this is the limit on the delimiter of
+/// C++ raw strings.
+pub const DEFAULT_MAX_LEN: usize = 16;
+
+/// The `char`s that a captured part can be made of.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum CaptureChars {
+    /// Only the listed `char`s
+    OneOf(Cow<'static, str>),
+    /// Any `char` except for the listed ones
+    NoneOf(Cow<'static, str>),
+}
+
+impl CaptureChars {
+    /// Whether `c` can be part of a captured part.
+    pub(crate) fn contains(&self, c: char) -> bool {
+        match self {
+            CaptureChars::OneOf(chars) => chars.contains(c),
+            CaptureChars::NoneOf(chars) => !chars.contains(c),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn default_max_len() -> usize {
+    DEFAULT_MAX_LEN
+}
+
+/// A variable part captured right after the open pattern of a comment or literal, which its
+/// close pattern has to repeat, such as the `=`s in Lua's `--[==[ ... ]==]` or the delimiter of
+/// C++'s `R"tag( ... )tag"`. The close pattern is built from the comment's close pattern, the
+/// captured part and the close suffix. The open pattern only matches if the captured part is
+/// followed by the end pattern.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::{Capture, Comment, IntoWithoutComments as _};
+/// let language = vec![
+///     Comment::line("--"),
+///     Comment::block("--[", "]").capture(Capture::one_of("=", "[").close_suffix("]")),
+/// ];
+/// let without_comments = "a --[==[ b ]] ]==] c -- d"
+///     .chars()
+///     .without_comments(language.into_boxed_slice())
+///     .collect::<String>();
+/// assert_eq!(&without_comments, "a  c ");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Capture {
+    /// The `char`s that the captured part can be made of
+    pub(crate) chars: CaptureChars,
+    /// Pattern ending the captured part in the opener, such as the second `[` in `--[==[`, if
+    /// it's empty, the captured part ends at the first `char` that can't be part of it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) end: Cow<'static, str>,
+    /// Minimum length of the captured part in `char`s
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) min_len: usize,
+    /// Maximum length of the captured part in `char`s, the open pattern doesn't match if the
+    /// part is longer
+    #[cfg_attr(feature = "serde", serde(default = "default_max_len"))]
+    pub(crate) max_len: usize,
+    /// Pattern following the captured part in the close pattern, such as the second `]` in
+    /// `]==]`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) close_suffix: Cow<'static, str>,
+}
+
+impl Capture {
+    fn new(chars: CaptureChars, end: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            chars,
+            end: end.into(),
+            min_len: 0,
+            max_len: DEFAULT_MAX_LEN,
+            close_suffix: Cow::Borrowed(""),
+        }
+    }
+
+    /// Captures a part made only of `chars` and ended by `end`.
+    pub fn one_of(chars: impl Into<Cow<'static, str>>, end: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(CaptureChars::OneOf(chars.into()), end)
+    }
+
+    /// Captures a part made of anything but `chars` and ended by `end`.
+    pub fn none_of(chars: impl Into<Cow<'static, str>>, end: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(CaptureChars::NoneOf(chars.into()), end)
+    }
+
+    /// Sets the minimum length of the captured part in `char`s, by default, 0.
+    pub fn min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Sets the maximum length of the captured part in `char`s, by default, `DEFAULT_MAX_LEN`.
+    /// `usize::MAX` lifts the limit, the buffer grows to hold whatever is captured.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Sets the pattern following the captured part in the close pattern, by default, none.
+    pub fn close_suffix(mut self, close_suffix: impl Into<Cow<'static, str>>) -> Self {
+        self.close_suffix = close_suffix.into();
+        self
+    }
+}
@@ -0,0 +1,201 @@
+use crate::languages::Language;
+use std::path::Path;
+
+/// Number of lines at the beginning and at the end of a file that are searched for modelines,
+/// this is the same as vim's default `modelines` setting.
+const MODELINE_LINES: usize = 5;
+
+/// Maps a language name as used by editors and interpreters (`python3`, `c++`, `runghc`, ...)
+/// to the corresponding language.
+fn language_by_name(name: &str) -> Option<Language> {
+    match name.to_lowercase().as_str() {
+        "runghc" | "runhaskell" => Some(Language::Haskell),
+        name => name.parse().ok(),
+    }
+}
+
+/// Maps a file extension (without the leading dot) to the corresponding language.
+fn language_by_extension(ext: &str) -> Option<Language> {
+    let language = match ext.to_lowercase().as_str() {
+        "rs" => Language::Rust,
+        "c" | "h" | "cc" | "cpp" | "cxx" | "c++" | "hh" | "hpp" | "hxx" | "h++" | "m" | "java"
+        | "js" | "mjs" | "cjs" | "ts" | "cs" | "go" => Language::C,
+        "py" | "pyw" | "pyi" => Language::Python,
+        "hs" => Language::Haskell,
+        "json" | "jsonc" | "json5" => Language::Json,
+        "apl" | "apla" | "aplc" | "aplf" | "apli" | "apln" | "aplo" | "dyalog" => Language::Apl,
+        "sh" | "bash" | "zsh" | "ksh" => Language::Shell,
+        "tex" | "sty" | "cls" | "ltx" | "dtx" => Language::Latex,
+        "bat" | "cmd" => Language::Batch,
+        "lua" => Language::Lua,
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Maps a whole file name (for files that are conventionally written without an extension) to
+/// the corresponding language.
+fn language_by_file_name(name: &str) -> Option<Language> {
+    let language = match name {
+        "SConstruct" | "SConscript" => Language::Python,
+        ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" => Language::Shell,
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Detects the language of a file from its name or extension, for example `main.rs` is rust
+/// and `lib.h` is C. Returns `None` if the name is not recognized.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::{detect, languages::Language};
+/// assert_eq!(detect::from_path("src/main.rs"), Some(Language::Rust));
+/// assert_eq!(detect::from_path("README"), None);
+/// ```
+pub fn from_path(path: impl AsRef<Path>) -> Option<Language> {
+    let path = path.as_ref();
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(language_by_file_name)
+        .or_else(|| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(language_by_extension)
+        })
+}
+
+/// Detects the language of a file from a shebang line such as `#!/usr/bin/env python3` or
+/// `#!/usr/bin/runghc`, only the first line of `text` is considered. Version suffixes of the
+/// interpreter (`python3.8`) are ignored. Returns `None` if there is no shebang or if the
+/// interpreter is not recognized.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::{detect, languages::Language};
+/// let script = "#!/usr/bin/env python3\nprint('hi')\n";
+/// assert_eq!(detect::from_shebang(script), Some(Language::Python));
+/// ```
+pub fn from_shebang(text: &str) -> Option<Language> {
+    let first_line = text.lines().next()?;
+    if !first_line.starts_with("#!") {
+        return None;
+    }
+
+    let mut words = first_line[2..].split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        // skip options given to env itself, such as `-S`
+        interpreter = words.find(|word| !word.starts_with('-'))?;
+    }
+
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    language_by_name(interpreter)
+}
+
+/// Detects the language of a file from a vim (`vim: set ft=python :`) or emacs
+/// (`-*- mode: haskell -*-`) modeline. Like vim, only the first and last few lines of `text`
+/// are searched. Returns `None` if no modeline is found or if the language it names is not
+/// recognized.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::{detect, languages::Language};
+/// let source = "/* -*- mode: c++; tab-width: 4 -*- */\nint main() {}\n";
+/// assert_eq!(detect::from_modeline(source), Some(Language::C));
+/// ```
+pub fn from_modeline(text: &str) -> Option<Language> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let tail_start = lines
+        .len()
+        .saturating_sub(MODELINE_LINES)
+        .max(MODELINE_LINES);
+    lines
+        .iter()
+        .take(MODELINE_LINES)
+        .chain(lines.iter().skip(tail_start))
+        .filter_map(|line| emacs_modeline(line).or_else(|| vim_modeline(line)))
+        .find_map(language_by_name)
+}
+
+/// Extracts the mode from an emacs modeline, either `-*- mode: name; ... -*-` or the short
+/// form `-*- name -*-`.
+fn emacs_modeline(line: &str) -> Option<&str> {
+    let start = line.find("-*-")? + 3;
+    let end = start + line[start..].find("-*-")?;
+    let vars = line[start..end].trim();
+
+    if !vars.contains(':') {
+        return Some(vars).filter(|mode| !mode.is_empty());
+    }
+
+    vars.split(';').find_map(|var| {
+        let mut parts = var.splitn(2, ':');
+        let key = parts.next()?.trim();
+        let value = parts.next()?.trim();
+        Some(value).filter(|_| key.eq_ignore_ascii_case("mode"))
+    })
+}
+
+/// Extracts the file type from a vim modeline such as `vim: ft=rust` or
+/// `vi: set filetype=c ts=4 :`, `syntax=` is accepted as well. Like in vim, the marker must be at
+/// the start of the line or follow whitespace, so that `regex: ft=python` is not a modeline.
+fn vim_modeline(line: &str) -> Option<&str> {
+    let start = ["vim:", "vi:", "ex:"]
+        .iter()
+        .flat_map(|marker| {
+            line.match_indices(marker)
+                .filter(|(idx, _)| {
+                    line[..*idx]
+                        .chars()
+                        .next_back()
+                        .map_or(true, char::is_whitespace)
+                })
+                .map(move |(idx, _)| idx + marker.len())
+        })
+        .min()?;
+
+    line[start..]
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .find_map(|option| {
+            let mut parts = option.splitn(2, '=');
+            match parts.next()? {
+                "ft" | "filetype" | "syn" | "syntax" => parts.next(),
+                _ => None,
+            }
+        })
+        .filter(|name| !name.is_empty())
+}
+
+/// Detects the language of a file using all of the available methods, in order of decreasing
+/// reliability: whole file names, then shebangs, then extensions. Modelines are only trusted when
+/// neither the file name nor the extension is recognized, in which case they come first. Returns
+/// `None` if none of them succeed.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::{detect, languages::Language};
+/// let script = "#!/usr/bin/env runghc\nmain = putStrLn \"hi\"\n";
+/// assert_eq!(detect::detect("hello", script), Some(Language::Haskell));
+/// assert_eq!(detect::detect("hello.rs", "fn main() {}"), Some(Language::Rust));
+/// assert_eq!(detect::detect("hello.rs", "// vim: ft=c"), Some(Language::Rust));
+/// ```
+pub fn detect(path: impl AsRef<Path>, text: &str) -> Option<Language> {
+    let path = path.as_ref();
+    let by_path = from_path(path);
+    by_path
+        .is_none()
+        .then(|| from_modeline(text))
+        .flatten()
+        .or_else(|| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(language_by_file_name)
+        })
+        .or_else(|| from_shebang(text))
+        .or(by_path)
+}
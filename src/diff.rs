@@ -0,0 +1,102 @@
+use crate::error::Error;
+use crate::without_comments::{Comment, IntoWithoutComments as _};
+
+/// Options for comparing texts without their comments.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Whether to ignore differences in whitespace, such as the whitespace left behind by a
+    /// removed comment. Runs of whitespace are treated as a single space, leading and trailing
+    /// whitespace on each line is ignored, and so are blank lines.
+    pub ignore_whitespace: bool,
+}
+
+/// A line of text without comments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    /// Number of the line of the original text that this line starts on, starting at 1
+    pub number: usize,
+    /// Text of the line without comments and without its line terminator
+    pub text: String,
+}
+
+/// Returns the lines of `text` without comments along with their original line numbers. With
+/// `options.ignore_whitespace`, the whitespace of each line is normalized and blank lines are
+/// skipped.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::diff::{lines, Options};
+/// use no_comment::languages;
+///
+/// let text = "a /* one\ntwo */ b\n// three\nc\n";
+/// let lines = lines(text.chars(), languages::c(), &Options::default()).unwrap();
+/// let lines = lines.iter().map(|l| (l.number, l.text.as_str())).collect::<Vec<_>>();
+/// assert_eq!(lines, [(1, "a  b"), (3, ""), (4, "c")]);
+/// ```
+pub fn lines(
+    text: impl Iterator<Item = char>,
+    language: Box<[Comment]>,
+    options: &Options,
+) -> Result<Vec<Line>, Error> {
+    let mut lines = text
+        .stripped_lines(language)
+        .map(|line| line.map(|(number, text)| Line { number, text }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if options.ignore_whitespace {
+        lines = lines
+            .into_iter()
+            .map(|line| Line {
+                number: line.number,
+                text: line.text.split_whitespace().collect::<Vec<_>>().join(" "),
+            })
+            .filter(|line| !line.text.is_empty())
+            .collect();
+    }
+    Ok(lines)
+}
+
+/// Whether two texts are the same once their comments are removed, for example, to find out
+/// whether a change only touched comments.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::diff::{equal, Options};
+/// use no_comment::languages;
+///
+/// let old = "fn f() {} // old comment\n";
+/// let new = "fn f() {} // new comment\n";
+/// assert!(equal(old.chars(), new.chars(), languages::rust(), &Options::default()).unwrap());
+///
+/// let new = "/* moved */\nfn f() {}\n";
+/// assert!(!equal(old.chars(), new.chars(), languages::rust(), &Options::default()).unwrap());
+/// let options = Options {
+///     ignore_whitespace: true,
+/// };
+/// assert!(equal(old.chars(), new.chars(), languages::rust(), &options).unwrap());
+/// ```
+pub fn equal(
+    a: impl Iterator<Item = char>,
+    b: impl Iterator<Item = char>,
+    language: Box<[Comment]>,
+    options: &Options,
+) -> Result<bool, Error> {
+    if options.ignore_whitespace {
+        let texts = |lines: Vec<Line>| lines.into_iter().map(|line| line.text);
+        let a = lines(a, language.clone(), options)?;
+        let b = lines(b, language, options)?;
+        return Ok(texts(a).eq(texts(b)));
+    }
+
+    let mut a = a.without_comments(language.clone());
+    let mut b = b.without_comments(language);
+    loop {
+        match (a.try_next()?, b.try_next()?) {
+            (None, None) => return Ok(true),
+            (x, y) if x != y => return Ok(false),
+            _ => (),
+        }
+    }
+}
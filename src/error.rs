@@ -0,0 +1,25 @@
+use crate::position::Position;
+use std::borrow::Cow;
+
+/// Error encountered while removing comments.
+#[derive(Clone, Debug, PartialEq, Eq, Display)]
+pub enum Error {
+    /// A close pattern that isn't allowed in regular text was found outside of a comment, for
+    /// example `*/` in rust
+    #[display(
+        fmt = "Got \"{}\" without matching \"{}\" at {}",
+        close_pat,
+        open_pat,
+        position
+    )]
+    UnmatchedClose {
+        /// The close pattern that was found
+        close_pat: Cow<'static, str>,
+        /// The open pattern that would have to precede it
+        open_pat: Cow<'static, str>,
+        /// Position of the first `char` of the close pattern in the input
+        position: Position,
+    },
+}
+
+impl std::error::Error for Error {}
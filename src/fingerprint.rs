@@ -0,0 +1,163 @@
+use crate::error::Error;
+use crate::read::ReadChars;
+use crate::whitespace::Normalizer;
+use crate::without_comments::{Comment, IntoWithoutComments as _};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read};
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// Options for fingerprinting texts without their comments.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Whether to ignore differences in whitespace, with the same rules as
+    /// `diff::Options::ignore_whitespace`
+    pub ignore_whitespace: bool,
+}
+
+/// A 128-bit fingerprint of a text without its comments. Fingerprints are computed with
+/// FNV-1a over the UTF-8 encoding of the text and are stable across platforms and versions of
+/// this crate, so they can be stored, for example as build cache keys. They aren't
+/// cryptographic hashes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint(pub u128);
+
+impl Display for Fingerprint {
+    /// Formats the fingerprint as 32 lowercase hexadecimal digits.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// Incremental computation of a `Fingerprint`, fed the output of `WithoutComments` one `char`
+/// at a time.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::fingerprint::{Fingerprinter, Options};
+/// use no_comment::{languages, IntoWithoutComments as _};
+///
+/// let mut fingerprinter = Fingerprinter::new(&Options::default());
+/// fingerprinter.extend("fn f() {} // comment\n".chars().without_comments(languages::rust()));
+///
+/// let mut other = Fingerprinter::new(&Options::default());
+/// other.extend("fn f() {} /* other */\n".chars().without_comments(languages::rust()));
+/// assert_eq!(fingerprinter.finish(), other.finish());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Fingerprinter {
+    state: u128,
+    normalizer: Option<Normalizer>,
+}
+
+impl Fingerprinter {
+    pub fn new(options: &Options) -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+            normalizer: if options.ignore_whitespace {
+                Some(Normalizer::default())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Adds `c` to the fingerprinted text.
+    pub fn push(&mut self, c: char) {
+        let state = &mut self.state;
+        let mut write = |c: char| {
+            for &byte in c.encode_utf8(&mut [0; 4]).as_bytes() {
+                *state ^= u128::from(byte);
+                *state = state.wrapping_mul(FNV_PRIME);
+            }
+        };
+        match &mut self.normalizer {
+            Some(normalizer) => normalizer.push(c, write),
+            None => write(c),
+        }
+    }
+
+    /// The fingerprint of the text pushed so far.
+    pub fn finish(&self) -> Fingerprint {
+        Fingerprint(self.state)
+    }
+}
+
+impl Extend<char> for Fingerprinter {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for c in iter {
+            self.push(c);
+        }
+    }
+}
+
+/// Computes the fingerprint of `text` without its comments, two texts that only differ in
+/// their comments have the same fingerprint.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::fingerprint::{fingerprint, Options};
+/// use no_comment::languages;
+///
+/// let options = Options {
+///     ignore_whitespace: true,
+/// };
+/// let a = fingerprint("x = 1  # one\n".chars(), languages::python(), &options).unwrap();
+/// let b = fingerprint("# header\nx = 1\n".chars(), languages::python(), &options).unwrap();
+/// assert_eq!(a, b);
+/// ```
+pub fn fingerprint(
+    text: impl Iterator<Item = char>,
+    language: Box<[Comment]>,
+    options: &Options,
+) -> Result<Fingerprint, Error> {
+    let mut fingerprinter = Fingerprinter::new(options);
+    let mut iter = text.without_comments(language);
+    while let Some(c) = iter.try_next()? {
+        fingerprinter.push(c);
+    }
+    Ok(fingerprinter.finish())
+}
+
+/// Computes the fingerprint of the text read from `reader` without its comments. The input is
+/// decoded and stripped as it's read, neither it nor the stripped text is ever held in memory
+/// as a whole.
+///
+/// If the input isn't valid UTF-8 or if stripping fails (because of an unmatched close
+/// pattern), an error of kind `io::ErrorKind::InvalidData` is returned, in the second case, the
+/// error wraps a `no_comment::Error`.
+///
+/// # Example
+///
+/// ```no_run
+/// use no_comment::fingerprint::{fingerprint_reader, Options};
+/// use no_comment::languages;
+/// use std::fs::File;
+///
+/// let file = File::open("src/main.rs").unwrap();
+/// let fingerprint = fingerprint_reader(file, languages::rust(), &Options::default()).unwrap();
+/// println!("{}", fingerprint);
+/// ```
+pub fn fingerprint_reader(
+    reader: impl Read,
+    language: Box<[Comment]>,
+    options: &Options,
+) -> io::Result<Fingerprint> {
+    let mut fingerprinter = Fingerprinter::new(options);
+    let mut iter = ReadChars::new(reader).without_comments(language);
+    let result = loop {
+        match iter.try_next() {
+            Ok(Some(c)) => fingerprinter.push(c),
+            Ok(None) => break Ok(fingerprinter.finish()),
+            Err(err) => break Err(err),
+        }
+    };
+    // a read error ends the input early, so it takes precedence over the result
+    if let Some(err) = iter.inner_mut().take_error() {
+        return Err(err);
+    }
+    result.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
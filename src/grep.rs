@@ -0,0 +1,160 @@
+use crate::error::Error;
+use crate::position::{ends_line, is_line_terminator, Position};
+use crate::without_comments::{Class, Comment, IntoWithoutComments as _};
+
+/// The part of a text to search.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Scope {
+    /// Only the text outside of comments
+    Code,
+    /// Only comments, including their open and close patterns
+    Comments,
+}
+
+/// The part of a line of the original text that is in a given `Scope`, for example, the code
+/// of `a /* b */ c` is `a  c`. `char`s that are out of scope are simply left out, so a search
+/// can match across them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopedLine {
+    /// Number of the line in the original text, starting at 1
+    pub number: usize,
+    /// The `char`s of the line that are in scope, without the line terminator
+    pub text: String,
+    /// The whole line in the original text, without the line terminator
+    pub original: String,
+    /// Start of each run of `char`s that are contiguous in the original text, as the byte
+    /// index in `text` and the position in the original text
+    runs: Vec<(usize, Position)>,
+}
+
+impl ScopedLine {
+    /// Position in the original text of the `char` at byte index `idx` of `text`, or of the end
+    /// of the last run if `idx` is the length of `text`.
+    pub fn position(&self, idx: usize) -> Position {
+        let run = self.runs.partition_point(|&(start, _)| start <= idx);
+        let (start, mut position) = self.runs[run.saturating_sub(1)];
+        position.advance_str(&self.text[start..idx]);
+        position
+    }
+}
+
+/// Splits `text` into lines keeping only the `char`s in `scope`, lines with no such `char`s
+/// are left out.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::grep::{scoped_lines, Scope};
+/// use no_comment::languages;
+///
+/// let lines = scoped_lines("f(); /* call */ g();\n// h();\n".chars(), languages::c(), Scope::Code)
+///     .unwrap();
+/// assert_eq!(lines.len(), 1);
+/// assert_eq!(lines[0].text, "f();  g();");
+/// assert_eq!(lines[0].position(6).column, 17);
+/// ```
+pub fn scoped_lines(
+    text: impl Iterator<Item = char>,
+    language: Box<[Comment]>,
+    scope: Scope,
+) -> Result<Vec<ScopedLine>, Error> {
+    let mut iter = text.without_comments(language);
+
+    let mut lines = Vec::new();
+    let mut current: Option<ScopedLine> = None;
+    // every char of the current line, in scope or not
+    let mut original = String::new();
+    // position right after the last char added to the current line
+    let mut next = Position::start();
+    let mut last = None;
+    while let Some((c, position, class)) = iter.next_classified()? {
+        let prev = last.replace(c);
+        if is_line_terminator(c) {
+            if ends_line(prev, c) {
+                let original = std::mem::take(&mut original);
+                lines.extend(current.take().map(|line| ScopedLine { original, ..line }));
+            }
+            continue;
+        }
+        original.push(c);
+
+        let in_scope = match class {
+            Class::Text | Class::Literal => scope == Scope::Code,
+            Class::CommentStart | Class::Comment => scope == Scope::Comments,
+        };
+        if !in_scope {
+            continue;
+        }
+
+        let line = current.get_or_insert_with(|| ScopedLine {
+            number: position.line,
+            text: String::new(),
+            original: String::new(),
+            runs: Vec::new(),
+        });
+        if line.runs.is_empty() || position != next {
+            line.runs.push((line.text.len(), position));
+        }
+        line.text.push(c);
+        next = position;
+        next.advance(prev, c);
+    }
+    lines.extend(current.map(|line| ScopedLine { original, ..line }));
+
+    Ok(lines)
+}
+
+/// An occurrence of a pattern found by `grep`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Match {
+    /// Position of the first `char` of the match in the original text
+    pub position: Position,
+    /// The matched text
+    pub text: String,
+    /// The part of the line that was searched, see `ScopedLine`
+    pub line: String,
+}
+
+/// Finds every (non-overlapping) occurrence of `pattern` in the given `scope` of `text`, each
+/// line is searched separately. Use `scoped_lines` to search with anything other than a plain
+/// string.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::grep::{grep, Scope};
+/// use no_comment::languages;
+///
+/// let text = "// old_api()\nnew_api(); /* not old_api() */ old_api();\n";
+/// let found = grep(text.chars(), languages::rust(), Scope::Code, "old_api").unwrap();
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].position.to_string(), "2:32");
+///
+/// let found = grep(text.chars(), languages::rust(), Scope::Comments, "old_api").unwrap();
+/// assert_eq!(found.len(), 2);
+/// ```
+pub fn grep(
+    text: impl Iterator<Item = char>,
+    language: Box<[Comment]>,
+    scope: Scope,
+    pattern: &str,
+) -> Result<Vec<Match>, Error> {
+    let mut found = Vec::new();
+    if pattern.is_empty() {
+        return Ok(found);
+    }
+
+    for line in scoped_lines(text, language, scope)? {
+        for (idx, text) in line.text.match_indices(pattern) {
+            found.push(Match {
+                position: line.position(idx),
+                text: text.to_string(),
+                line: line.text.clone(),
+            });
+        }
+    }
+    Ok(found)
+}
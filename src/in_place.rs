@@ -0,0 +1,103 @@
+use crate::without_comments::{Comment, IntoWithoutComments as _};
+use std::ffi::OsString;
+use std::fs::{self, File, FileTimes, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Options for `strip_in_place`.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// If set, the original file is copied to its path with this suffix appended (for example
+    /// `main.rs.orig` for a suffix of `.orig`) before being replaced
+    pub backup_suffix: Option<String>,
+    /// Whether to keep the access and modification times of the original file
+    pub preserve_times: bool,
+}
+
+/// Removes comments from a file, replacing it atomically. The whole file is stripped in memory
+/// before anything is written, the result is then written to a temporary file in the same
+/// directory which is renamed over the original. The permissions of the original file are
+/// always kept. If the file is a symbolic link, its target is replaced.
+///
+/// Returns `Ok(false)` without touching the file if it contains no comments. If the file isn't
+/// valid UTF-8 or if stripping fails (because of an unmatched close pattern), an error of kind
+/// `io::ErrorKind::InvalidData` is returned and the file is left untouched, in the second case,
+/// the error wraps a `no_comment::Error`.
+///
+/// # Example
+///
+/// ```no_run
+/// use no_comment::in_place::{strip_in_place, Options};
+/// use no_comment::languages;
+///
+/// let options = Options {
+///     backup_suffix: Some(".orig".to_string()),
+///     ..Options::default()
+/// };
+/// strip_in_place("src/main.rs", languages::rust(), &options).unwrap();
+/// ```
+pub fn strip_in_place(
+    path: impl AsRef<Path>,
+    language: Box<[Comment]>,
+    options: &Options,
+) -> io::Result<bool> {
+    let path = fs::canonicalize(path)?;
+    let text = fs::read_to_string(&path)?;
+    let stripped = text
+        .chars()
+        .without_comments(language)
+        .try_collect::<String>()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    if stripped == text {
+        return Ok(false);
+    }
+
+    let metadata = fs::metadata(&path)?;
+    let (tmp_path, mut tmp) = create_temp_file(&path)?;
+    let result = (|| {
+        tmp.write_all(stripped.as_bytes())?;
+        tmp.set_permissions(metadata.permissions())?;
+        if options.preserve_times {
+            let times = FileTimes::new()
+                .set_accessed(metadata.accessed()?)
+                .set_modified(metadata.modified()?);
+            tmp.set_times(times)?;
+        }
+        tmp.sync_all()?;
+
+        if let Some(suffix) = &options.backup_suffix {
+            let mut backup = OsString::from(&path);
+            backup.push(suffix);
+            fs::copy(&path, backup)?;
+        }
+
+        fs::rename(&tmp_path, &path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result.map(|()| true)
+}
+
+/// Creates a new temporary file next to `path`, failing if every candidate name is taken.
+fn create_temp_file(path: &Path) -> io::Result<(PathBuf, File)> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    let mut attempt = 0;
+    loop {
+        let tmp_path = dir.join(format!(".{}.{}.{}.tmp", name, process::id(), attempt));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)
+        {
+            Ok(file) => return Ok((tmp_path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
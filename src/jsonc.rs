@@ -0,0 +1,178 @@
+use crate::error::Error;
+use crate::languages;
+use crate::read::ReadChars;
+use crate::without_comments::{Class, IntoWithoutComments as _, WithoutComments};
+use std::io::{self, Read};
+
+/// Number of bytes that `Reader` strips at a time.
+const CHUNK_LEN: usize = 8 * 1024;
+
+/// Options for stripping JSON with comments.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Whether to remove trailing commas (`[1, 2,]` becomes `[1, 2]`) so that the result is
+    /// valid JSON, commas in strings are never removed
+    pub remove_trailing_commas: bool,
+}
+
+/// Holds back commas until the next non-whitespace `char` shows whether they are trailing.
+#[derive(Debug, Default)]
+struct TrailingCommas {
+    /// A comma followed by whitespace, not yet written
+    held: Option<String>,
+}
+
+impl TrailingCommas {
+    fn push(&mut self, c: char, class: Class, out: &mut String) {
+        let in_literal = class == Class::Literal;
+        if let Some(held) = self.held.take() {
+            if !in_literal && c.is_whitespace() {
+                self.held = Some(held + c.encode_utf8(&mut [0; 4]));
+                return;
+            }
+            match !in_literal && (c == '}' || c == ']') {
+                // drop the comma, keep the whitespace
+                true => out.push_str(&held[1..]),
+                false => out.push_str(&held),
+            }
+        }
+
+        match !in_literal && c == ',' {
+            true => self.held = Some(c.to_string()),
+            false => out.push(c),
+        }
+    }
+
+    fn finish(&mut self, out: &mut String) {
+        out.extend(self.held.take());
+    }
+}
+
+/// Removes the comments (and optionally the trailing commas) from JSON with comments, using
+/// `languages::json`. Returns an error if `WithoutComments` would panic.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::jsonc::{strip, Options};
+///
+/// let text = r#"{"url": "http://x", /* port */ "ports": [80, 443,], // done
+/// }"#;
+/// let options = Options {
+///     remove_trailing_commas: true,
+/// };
+/// let json = strip(text.chars(), &options).unwrap();
+/// assert_eq!(json, "{\"url\": \"http://x\",  \"ports\": [80, 443] \n}");
+/// ```
+pub fn strip(text: impl Iterator<Item = char>, options: &Options) -> Result<String, Error> {
+    let mut iter = text.without_comments(languages::json());
+    let mut commas = TrailingCommas::default();
+
+    let mut out = String::new();
+    while let Some((c, _, class)) = iter.next_classified()? {
+        if !class.is_text() {
+            continue;
+        }
+        match options.remove_trailing_commas {
+            true => commas.push(c, class, &mut out),
+            false => out.push(c),
+        }
+    }
+    commas.finish(&mut out);
+    Ok(out)
+}
+
+/// `Read` adapter removing the comments (and optionally the trailing commas) from JSON with
+/// comments as it's read, so that it can be passed to `serde_json::from_reader` or any other
+/// JSON parser. The input is processed in chunks and never held in memory as a whole.
+///
+/// If the input isn't valid UTF-8 or if stripping fails (because of an unmatched `*/`), reading
+/// returns an error of kind `io::ErrorKind::InvalidData`, in the second case, the error wraps a
+/// `no_comment::Error`.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::jsonc::{Options, Reader};
+/// use std::io::Read as _;
+///
+/// let config = "{\n  // the name\n  \"name\": \"a // b\",\n}\n".as_bytes();
+/// let options = Options {
+///     remove_trailing_commas: true,
+/// };
+/// let mut json = String::new();
+/// Reader::new(config, &options).read_to_string(&mut json).unwrap();
+/// assert_eq!(json, "{\n  \n  \"name\": \"a // b\"\n}\n");
+/// ```
+pub struct Reader<R: Read> {
+    inner: WithoutComments<ReadChars<R>>,
+    /// `None` if trailing commas are kept
+    commas: Option<TrailingCommas>,
+    /// Stripped text not yet read
+    out: Vec<u8>,
+    /// Number of bytes of `out` already read
+    read: usize,
+    /// Whether the end of the input has been reached
+    done: bool,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(reader: R, options: &Options) -> Self {
+        Self {
+            inner: ReadChars::new(reader).without_comments(languages::json()),
+            commas: match options.remove_trailing_commas {
+                true => Some(TrailingCommas::default()),
+                false => None,
+            },
+            out: Vec::new(),
+            read: 0,
+            done: false,
+        }
+    }
+
+    /// Strips the next chunk of the input into `out`.
+    fn fill(&mut self) -> io::Result<()> {
+        let mut out = String::new();
+        while out.len() < CHUNK_LEN {
+            let next = self
+                .inner
+                .next_classified()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            match next {
+                Some((c, _, class)) if class.is_text() => match &mut self.commas {
+                    Some(commas) => commas.push(c, class, &mut out),
+                    None => out.push(c),
+                },
+                Some(_) => (),
+                None => {
+                    // a read error ends the input early
+                    if let Some(err) = self.inner.inner_mut().take_error() {
+                        return Err(err);
+                    }
+                    if let Some(commas) = &mut self.commas {
+                        commas.finish(&mut out);
+                    }
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+
+        self.out = out.into_bytes();
+        self.read = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.read == self.out.len() && !self.done {
+            self.fill()?;
+        }
+
+        let n = buf.len().min(self.out.len() - self.read);
+        buf[..n].copy_from_slice(&self.out[self.read..self.read + n]);
+        self.read += n;
+        Ok(n)
+    }
+}
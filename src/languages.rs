@@ -1,98 +1,360 @@
-use crate::without_comments::Comment;
-
-/// Macro to generate getter a function from a constant like `fn rust() -> Box[Comment]` from
-/// `const RUST: [Comment; 2]`. These getters are the only public interface of this module,
-/// used as no_comment::languages::rust(), etc.
-macro_rules! make_getter {
-    (const $c:ident: [Comment; $_:expr], pub fn $f:ident) => {
-        #[allow(dead_code)]
-        pub fn $f() -> Box<[Comment]> {
-            $c.iter().copied().collect::<Vec<_>>().into_boxed_slice()
+use crate::capture::{Capture, CaptureChars};
+use crate::without_comments::{Anchor, CharClass, Comment, LINE_TERMINATORS};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+/// The built-in languages. A `Language` can be parsed from its name or one of its aliases
+/// (case-insensitive) and displays as its human-readable name.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::languages::{self, Language};
+/// let language = "C++".parse::<Language>().unwrap();
+/// assert_eq!(language, Language::C);
+/// assert_eq!(language.comments(), languages::c());
+/// assert_eq!(language.to_string(), "C");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Language {
+    Rust,
+    C,
+    Python,
+    Haskell,
+    Json,
+    Apl,
+    Shell,
+    Latex,
+    Batch,
+    Lua,
+}
+
+impl Language {
+    /// All of the built-in languages.
+    pub const ALL: [Language; 10] = [
+        Language::Rust,
+        Language::C,
+        Language::Python,
+        Language::Haskell,
+        Language::Json,
+        Language::Apl,
+        Language::Shell,
+        Language::Latex,
+        Language::Batch,
+        Language::Lua,
+    ];
+
+    /// Iterate over all of the built-in languages.
+    pub fn iter() -> impl Iterator<Item = Language> {
+        Self::ALL.iter().copied()
+    }
+
+    /// The canonical name of the language, such as `"rust"`, this is the name of the matching
+    /// getter in this module.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::C => "c",
+            Language::Python => "python",
+            Language::Haskell => "haskell",
+            Language::Json => "json",
+            Language::Apl => "apl",
+            Language::Shell => "shell",
+            Language::Latex => "latex",
+            Language::Batch => "batch",
+            Language::Lua => "lua",
+        }
+    }
+
+    /// The human-readable name of the language, such as `"Rust"`.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::C => "C",
+            Language::Python => "Python",
+            Language::Haskell => "Haskell",
+            Language::Json => "JSON",
+            Language::Apl => "APL",
+            Language::Shell => "Shell",
+            Language::Latex => "LaTeX",
+            Language::Batch => "Batch",
+            Language::Lua => "Lua",
+        }
+    }
+
+    /// Other names that the language can be parsed from. Languages with the same comment
+    /// syntax share a definition, for example, C++ and Java are aliases of C.
+    pub fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["rs"],
+            Language::C => &[
+                "c++",
+                "cpp",
+                "cc",
+                "cxx",
+                "h",
+                "hpp",
+                "objc",
+                "java",
+                "javascript",
+                "js",
+                "typescript",
+                "ts",
+                "c#",
+                "csharp",
+                "cs",
+                "go",
+            ],
+            Language::Python => &["py", "python2", "python3"],
+            Language::Haskell => &["hs"],
+            Language::Json => &["jsonc", "json5"],
+            Language::Apl => &["dyalog"],
+            Language::Shell => &["sh", "bash", "zsh", "ksh"],
+            Language::Latex => &["tex"],
+            Language::Batch => &["bat", "cmd"],
+            Language::Lua => &["luajit"],
         }
-    };
+    }
+
+    /// The comment specifications of the language, as consumed by `without_comments`.
+    pub fn comments(self) -> Box<[Comment]> {
+        match self {
+            Language::Rust => rust(),
+            Language::C => c(),
+            Language::Python => python(),
+            Language::Haskell => haskell(),
+            Language::Json => json(),
+            Language::Apl => apl(),
+            Language::Shell => shell(),
+            Language::Latex => latex(),
+            Language::Batch => batch(),
+            Language::Lua => lua(),
+        }
+    }
+
+    /// Whether `name` is the name or one of the aliases of the language, ignoring case.
+    pub(crate) fn is_called(self, name: &str) -> bool {
+        self.name().eq_ignore_ascii_case(name)
+            || self
+                .aliases()
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    }
 }
 
-make_getter!(const RUST: [Comment; 2], pub fn rust);
-make_getter!(const C: [Comment; 2], pub fn c);
-make_getter!(const PYTHON: [Comment; 3], pub fn python);
-make_getter!(const HASKELL: [Comment; 2], pub fn haskell);
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
 
-#[allow(dead_code)]
-const RUST: [Comment; 2] = [
-    Comment {
-        open_pat: "//",
-        close_pat: "\n",
-        nests: false,
-        keep_close_pat: true,
-        allow_close_pat: true,
-    },
-    Comment {
-        open_pat: "/*",
-        close_pat: "*/",
-        nests: true,
-        keep_close_pat: false,
-        allow_close_pat: false,
-    },
-];
+impl FromStr for Language {
+    type Err = UnknownLanguage;
 
-#[allow(dead_code)]
-const C: [Comment; 2] = [
-    Comment {
-        open_pat: "//",
-        close_pat: "\n",
-        nests: false,
-        keep_close_pat: true,
-        allow_close_pat: true,
-    },
-    Comment {
-        open_pat: "/*",
-        close_pat: "*/",
-        nests: false,
-        keep_close_pat: false,
-        allow_close_pat: false,
-    },
-];
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::iter()
+            .find(|language| language.is_called(s))
+            .ok_or_else(|| UnknownLanguage(s.to_string()))
+    }
+}
 
-#[allow(dead_code)]
-const PYTHON: [Comment; 3] = [
-    Comment {
-        open_pat: "#",
-        close_pat: "\n",
-        nests: false,
-        keep_close_pat: true,
-        allow_close_pat: true,
-    },
-    // allow_close_pat won't be checked because open_pat will match first
-    Comment {
-        open_pat: "'''",
-        close_pat: "'''",
-        nests: false,
-        keep_close_pat: false,
-        allow_close_pat: false,
-    },
+/// Error returned when parsing a `Language` from a name that isn't known.
+#[derive(Clone, Debug, PartialEq, Eq, Display)]
+#[display(fmt = "unknown language \"{}\"", _0)]
+pub struct UnknownLanguage(pub String);
+
+impl std::error::Error for UnknownLanguage {}
+
+/// Same as `Comment::block` but with borrowed patterns, the built-in comments are built from it
+/// (or from `line` and `literal`) with struct update syntax. The getters below can't be constants
+/// because struct update syntax drops the unused fields of its base, which constants can't do
+/// with the `regex` feature's `Vec`s.
+const fn block(
+    open_pats: &'static [Cow<'static, str>],
+    close_pats: &'static [Cow<'static, str>],
+) -> Comment {
     Comment {
-        open_pat: "\"\"\"",
-        close_pat: "\"\"\"",
+        open_pats: Cow::Borrowed(open_pats),
+        close_pats: Cow::Borrowed(close_pats),
         nests: false,
         keep_close_pat: false,
         allow_close_pat: false,
-    },
-];
+        literal: false,
+        escape: None,
+        priority: 0,
+        open_anchor: Anchor::Anywhere,
+        close_anchor: Anchor::Anywhere,
+        preceded_by: CharClass::Any,
+        followed_by: CharClass::Any,
+        ignore_case: false,
+        capture: None,
+        #[cfg(feature = "regex")]
+        open_regexes: Vec::new(),
+        #[cfg(feature = "regex")]
+        close_regexes: Vec::new(),
+    }
+}
 
-#[allow(dead_code)]
-const HASKELL: [Comment; 2] = [
+/// Same as `Comment::line` but with borrowed patterns.
+fn line(open_pats: &'static [Cow<'static, str>]) -> Comment {
     Comment {
-        open_pat: "--",
-        close_pat: "\n",
-        nests: false,
         keep_close_pat: true,
         allow_close_pat: true,
-    },
+        ..block(open_pats, LINE_TERMINATORS)
+    }
+}
+
+/// Same as `Comment::literal` but with borrowed patterns.
+fn literal(
+    open_pats: &'static [Cow<'static, str>],
+    close_pats: &'static [Cow<'static, str>],
+) -> Comment {
     Comment {
-        open_pat: "{-",
-        close_pat: "-}",
-        nests: true,
-        keep_close_pat: false,
-        allow_close_pat: false,
-    },
-];
+        literal: true,
+        ..block(open_pats, close_pats)
+    }
+}
+
+pub fn rust() -> Box<[Comment]> {
+    Box::new([
+        line(&[Cow::Borrowed("//")]),
+        Comment {
+            nests: true,
+            ..block(&[Cow::Borrowed("/*")], &[Cow::Borrowed("*/")])
+        },
+    ])
+}
+
+pub fn c() -> Box<[Comment]> {
+    Box::new([
+        line(&[Cow::Borrowed("//")]),
+        block(&[Cow::Borrowed("/*")], &[Cow::Borrowed("*/")]),
+    ])
+}
+
+pub fn python() -> Box<[Comment]> {
+    Box::new([
+        line(&[Cow::Borrowed("#")]),
+        // allow_close_pat won't be checked because open_pat will match first
+        block(&[Cow::Borrowed("'''")], &[Cow::Borrowed("'''")]),
+        block(&[Cow::Borrowed("\"\"\"")], &[Cow::Borrowed("\"\"\"")]),
+    ])
+}
+
+pub fn haskell() -> Box<[Comment]> {
+    Box::new([
+        line(&[Cow::Borrowed("--")]),
+        Comment {
+            nests: true,
+            ..block(&[Cow::Borrowed("{-")], &[Cow::Borrowed("-}")])
+        },
+    ])
+}
+
+/// JSON with comments (JSONC), strings are literals so that `"http://x"` is kept intact. Single
+/// quoted strings are literals too, as in JSON5.
+pub fn json() -> Box<[Comment]> {
+    Box::new([
+        line(&[Cow::Borrowed("//")]),
+        block(&[Cow::Borrowed("/*")], &[Cow::Borrowed("*/")]),
+        Comment {
+            escape: Some('\\'),
+            ..literal(&[Cow::Borrowed("\"")], &[Cow::Borrowed("\"")])
+        },
+        Comment {
+            escape: Some('\\'),
+            ..literal(&[Cow::Borrowed("'")], &[Cow::Borrowed("'")])
+        },
+    ])
+}
+
+// quotes are doubled inside of strings ('it''s'), which works as closing and reopening
+pub fn apl() -> Box<[Comment]> {
+    Box::new([
+        line(&[Cow::Borrowed("⍝")]),
+        literal(&[Cow::Borrowed("'")], &[Cow::Borrowed("'")]),
+        literal(&[Cow::Borrowed("\"")], &[Cow::Borrowed("\"")]),
+    ])
+}
+
+// `#` only starts a comment after whitespace, `a#b` and `$#` aren't comments, and a `#!` shebang
+// line at the start of the file is kept as a literal, outside of quotes `\'`, `\"` and `\#` are
+// escaped and don't start anything
+pub fn shell() -> Box<[Comment]> {
+    Box::new([
+        Comment {
+            escape: Some('\\'),
+            preceded_by: CharClass::Whitespace,
+            ..line(&[Cow::Borrowed("#")])
+        },
+        literal(&[Cow::Borrowed("'")], &[Cow::Borrowed("'")]),
+        Comment {
+            escape: Some('\\'),
+            ..literal(&[Cow::Borrowed("\"")], &[Cow::Borrowed("\"")])
+        },
+        Comment {
+            allow_close_pat: true,
+            open_anchor: Anchor::FileStart,
+            ..literal(&[Cow::Borrowed("#!")], LINE_TERMINATORS)
+        },
+    ])
+}
+
+// `\%` is an escaped percent sign rather than a comment
+pub fn latex() -> Box<[Comment]> {
+    Box::new([Comment {
+        escape: Some('\\'),
+        ..line(&[Cow::Borrowed("%")])
+    }])
+}
+
+// `REM` is a command, so it's only a comment at the start of a line, in any case, and as a whole
+// word (`REM.` is a comment too but `REMARK` isn't)
+pub fn batch() -> Box<[Comment]> {
+    Box::new([
+        Comment {
+            open_anchor: Anchor::Indented,
+            followed_by: CharClass::NonWord,
+            ignore_case: true,
+            ..line(&[Cow::Borrowed("REM"), Cow::Borrowed("@REM")])
+        },
+        Comment {
+            open_anchor: Anchor::Indented,
+            ..line(&[Cow::Borrowed("::")])
+        },
+    ])
+}
+
+/// The `=`s between the brackets of a Lua long bracket, any number of them.
+const LONG_BRACKET: Capture = Capture {
+    chars: CaptureChars::OneOf(Cow::Borrowed("=")),
+    end: Cow::Borrowed("["),
+    min_len: 0,
+    max_len: usize::MAX,
+    close_suffix: Cow::Borrowed("]"),
+};
+
+// long brackets (`[[ ... ]]`, `[==[ ... ]==]`) are closed by a bracket with as many `=`s, any
+// number of them, they delimit both block comments (after `--`) and long strings
+pub fn lua() -> Box<[Comment]> {
+    Box::new([
+        line(&[Cow::Borrowed("--")]),
+        Comment {
+            capture: Some(LONG_BRACKET),
+            ..block(&[Cow::Borrowed("--[")], &[Cow::Borrowed("]")])
+        },
+        Comment {
+            escape: Some('\\'),
+            ..literal(&[Cow::Borrowed("\"")], &[Cow::Borrowed("\"")])
+        },
+        Comment {
+            escape: Some('\\'),
+            ..literal(&[Cow::Borrowed("'")], &[Cow::Borrowed("'")])
+        },
+        Comment {
+            capture: Some(LONG_BRACKET),
+            ..literal(&[Cow::Borrowed("[")], &[Cow::Borrowed("]")])
+        },
+    ])
+}
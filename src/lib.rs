@@ -3,11 +3,38 @@
 #[macro_use]
 extern crate derive_more;
 
-pub use without_comments::{IntoWithoutComments, WithoutComments};
+pub use capture::Capture;
+pub use error::Error;
+pub use lines::StrippedLines;
+pub use position::Position;
+#[cfg(feature = "regex")]
+pub use regex_pattern::{RegexError, RegexPattern};
+pub use segments::{Segment, SegmentKind, Segments};
+pub use without_comments::{Anchor, CharClass, Comment, IntoWithoutComments, WithoutComments};
 
 // TODO redocument, update readmes, mention that comments started in strings are still comments ("/*" starts a block comment)
 
+pub mod annotations;
+pub mod capture;
+pub mod detect;
+pub mod diff;
+mod error;
+pub mod fingerprint;
+pub mod grep;
+pub mod in_place;
+pub mod jsonc;
 pub mod languages;
+mod lines;
+mod position;
+mod read;
+#[cfg(feature = "regex")]
+pub mod regex_pattern;
+pub mod registry;
+mod segments;
+pub mod source_map;
+pub mod stats;
+pub mod validation;
+mod whitespace;
 mod without_comments;
 
 #[cfg(test)]
@@ -0,0 +1,112 @@
+use crate::error::Error;
+use crate::position::{ends_line, is_line_terminator};
+use crate::without_comments::WithoutComments;
+
+/// Iterator over the lines of its input without comments, each paired with the number of the
+/// original line it starts on (starting at 1). Lines don't include their line terminator (`\n`,
+/// `\r\n`, `\r`, `U+2028` or `U+2029`) and a trailing one doesn't start a new line. A line left
+/// behind by a block comment spanning several lines is numbered after the line that the comment
+/// ends on. Returns an error if `WithoutComments` would panic, after which iteration stops.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::{languages, IntoWithoutComments as _};
+/// let text = "a /* one\ntwo */ b\n    // three\nc\n";
+/// let lines = text
+///     .chars()
+///     .stripped_lines(languages::c())
+///     .map(Result::unwrap)
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     lines,
+///     [(1, "a  b".to_string()), (3, "    ".to_string()), (4, "c".to_string())]
+/// );
+/// ```
+pub struct StrippedLines<I: Iterator<Item = char>> {
+    /// Inner iterator, used for its classification of `char`s
+    inner: WithoutComments<I>,
+    /// Whether to skip the lines that only contain whitespace because of a comment
+    skip_emptied: bool,
+    /// Whether an error has been returned
+    failed: bool,
+    /// The last `char` of the input, text or not
+    prev: Option<char>,
+}
+
+impl<I: Iterator<Item = char>> StrippedLines<I> {
+    pub(crate) fn new(inner: WithoutComments<I>) -> Self {
+        Self {
+            inner,
+            skip_emptied: false,
+            failed: false,
+            prev: None,
+        }
+    }
+
+    /// Sets whether to skip the lines that only contain whitespace once their comments are
+    /// removed, lines that were already blank are always kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{languages, IntoWithoutComments as _};
+    /// let text = "a\n    // b\n\n/* c */\nd";
+    /// let numbers = text
+    ///     .chars()
+    ///     .stripped_lines(languages::rust())
+    ///     .skip_emptied(true)
+    ///     .map(|line| line.unwrap().0)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(numbers, [1, 3, 5]);
+    /// ```
+    pub fn skip_emptied(mut self, skip_emptied: bool) -> Self {
+        self.skip_emptied = skip_emptied;
+        self
+    }
+
+    fn next_line(&mut self) -> Result<Option<(usize, String)>, Error> {
+        // the current line's number and text, and whether it had a comment
+        let mut current: Option<(usize, String)> = None;
+        let mut has_comment = false;
+        while let Some((c, position, class)) = self.inner.next_classified()? {
+            let prev = self.prev.replace(c);
+            if !class.is_text() {
+                has_comment = true;
+                continue;
+            }
+            if is_line_terminator(c) && !ends_line(prev, c) {
+                continue;
+            }
+
+            let line = current.get_or_insert_with(|| (position.line, String::new()));
+            if !is_line_terminator(c) {
+                line.1.push(c);
+                continue;
+            }
+
+            let emptied = has_comment && line.1.trim().is_empty();
+            if self.skip_emptied && emptied {
+                current = None;
+                has_comment = false;
+            } else {
+                return Ok(current);
+            }
+        }
+
+        Ok(current.filter(|line| !(self.skip_emptied && has_comment && line.1.trim().is_empty())))
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for StrippedLines<I> {
+    type Item = Result<(usize, String), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.next_line();
+        self.failed = result.is_err();
+        result.transpose()
+    }
+}
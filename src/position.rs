@@ -0,0 +1,60 @@
+/// A position in the input of a `WithoutComments` iterator.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[display(fmt = "{}:{}", line, column)]
+pub struct Position {
+    /// Offset in bytes from the beginning of the input, as if it were UTF-8 encoded
+    pub offset: usize,
+    /// Line number, starting at 1, lines end with `\n`, `\r\n`, `\r`, `U+2028` or `U+2029`
+    pub line: usize,
+    /// Column number in `char`s, starting at 1
+    pub column: usize,
+}
+
+impl Position {
+    /// The position of the first `char` of the input.
+    pub fn start() -> Self {
+        Self {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Moves the position past `c`, `prev` being the `char` before it.
+    pub(crate) fn advance(&mut self, prev: Option<char>, c: char) {
+        self.offset += c.len_utf8();
+        if ends_line(prev, c) {
+            self.line += 1;
+            self.column = 1;
+        } else if !is_line_terminator(c) {
+            self.column += 1;
+        }
+    }
+
+    /// Moves the position past `text`, which doesn't start with the `\n` of a `\r\n`.
+    pub(crate) fn advance_str(&mut self, text: &str) {
+        let mut prev = None;
+        for c in text.chars() {
+            self.advance(prev, c);
+            prev = Some(c);
+        }
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+/// Whether `c` is part of one of the `LINE_TERMINATORS`.
+pub(crate) fn is_line_terminator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+/// Whether `c` ends a line, `prev` being the `char` before it. The `\n` of a `\r\n` doesn't, the
+/// line already ended at the `\r`.
+pub(crate) fn ends_line(prev: Option<char>, c: char) -> bool {
+    is_line_terminator(c) && !(c == '\n' && prev == Some('\r'))
+}
@@ -0,0 +1,76 @@
+use std::io::{self, BufRead, BufReader, Read};
+
+/// `char` iterator decoding UTF-8 from a reader as it goes. Iteration stops at the first error,
+/// which can then be retrieved with `take_error`.
+pub(crate) struct ReadChars<R: Read> {
+    reader: BufReader<R>,
+    error: Option<io::Error>,
+}
+
+impl<R: Read> ReadChars<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            error: None,
+        }
+    }
+
+    /// Returns the error that stopped the iteration, if any.
+    pub(crate) fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    fn next_char(&mut self) -> io::Result<Option<char>> {
+        let first = loop {
+            match self.reader.fill_buf() {
+                Ok([]) => return Ok(None),
+                Ok(buf) => break buf[0],
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => return Err(err),
+            }
+        };
+
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )
+        };
+        let len = match first {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return Err(invalid()),
+        };
+
+        let mut buf = [0; 4];
+        self.reader
+            .read_exact(&mut buf[..len])
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::UnexpectedEof => invalid(),
+                _ => err,
+            })?;
+        match std::str::from_utf8(&buf[..len]) {
+            Ok(s) => Ok(s.chars().next()),
+            Err(_) => Err(invalid()),
+        }
+    }
+}
+
+impl<R: Read> Iterator for ReadChars<R> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.error.is_some() {
+            return None;
+        }
+        match self.next_char() {
+            Ok(c) => c,
+            Err(err) => {
+                self.error = Some(err);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use regex_automata::hybrid::dfa::{Cache, DFA};
+use regex_automata::util::start;
+use regex_automata::Anchored;
+use std::sync::Mutex;
+
+/// Maximum number of `char`s that the buffer grows to when matching a regular expression, this
+/// keeps memory bounded. The buffer only grows while more input could still change the match,
+/// which takes one `char` past its end, a regular expression that is still undecided after this
+/// many `char`s keeps the longest match found so far, if any.
+pub const MAX_LOOKAHEAD: usize = 4096;
+
+/// A regular expression that can't be compiled.
+#[derive(Clone, Debug, PartialEq, Eq, Display)]
+#[display(fmt = "invalid regular expression {:?}: {}", regex, message)]
+pub struct RegexError {
+    /// The regular expression as given
+    pub regex: String,
+    /// What is wrong with it, including the syntax error if any
+    pub message: String,
+}
+
+impl std::error::Error for RegexError {}
+
+/// A regular expression used as an open or close pattern, see `Comment::open_regex`. It is
+/// anchored, that is, it only matches at the current position in the input, the `char`
+/// before it is taken into account by look-behind assertions such as `(?m:^)`, and an empty
+/// match doesn't count as a match.
+///
+/// Regular expressions are matched by a lazy DFA from the `regex-automata` crate, one `char` at
+/// a time, they support the syntax of the `regex` crate except for Unicode word boundaries, use
+/// `(?-u:\b)` instead of `\b`.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::RegexPattern;
+/// assert!(RegexPattern::new(r"\d+:").is_ok());
+/// assert!(RegexPattern::new(r"(").is_err());
+/// assert!(RegexPattern::new(r"(?-u:\b)REM(?-u:\b)").is_ok());
+/// ```
+#[derive(Debug)]
+pub struct RegexPattern {
+    /// The regular expression as given
+    source: String,
+    dfa: DFA,
+    /// States of the lazy DFA computed so far
+    cache: Mutex<Cache>,
+}
+
+impl RegexPattern {
+    /// Compiles a regular expression.
+    pub fn new(re: &str) -> Result<Self, RegexError> {
+        let dfa = DFA::new(re).map_err(|err| {
+            // the syntax error itself is at the end of the chain of sources
+            let mut message = err.to_string();
+            let mut source = std::error::Error::source(&err);
+            while let Some(err) = source {
+                message = format!("{}: {}", message, err);
+                source = err.source();
+            }
+            RegexError {
+                regex: re.to_string(),
+                message,
+            }
+        })?;
+        Ok(Self {
+            source: re.to_string(),
+            cache: Mutex::new(dfa.create_cache()),
+            dfa,
+        })
+    }
+
+    /// The regular expression as given.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Length in `char`s of the non-empty match at the start of the input, if any, preferring
+    /// the same match as the `regex` crate when there are several. `prev` is the `char`
+    /// preceding the input and `char_at` returns the `char` at an index of the input, or `None`
+    /// past its end, it's called with increasing indices.
+    pub(crate) fn match_len(
+        &self,
+        prev: Option<char>,
+        mut char_at: impl FnMut(usize) -> Option<char>,
+    ) -> Option<usize> {
+        let mut cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+        let cache = &mut *cache;
+        let mut utf8 = [0; 4];
+        let look_behind = prev.map(|c| *c.encode_utf8(&mut utf8).as_bytes().last().unwrap());
+        let config = start::Config::new()
+            .anchored(Anchored::Yes)
+            .look_behind(look_behind);
+        // without quit bytes and with the default cache settings, the lazy DFA never fails
+        let mut state = self.dfa.start_state(cache, &config).unwrap();
+
+        let mut len = None;
+        for index in 0..MAX_LOOKAHEAD {
+            let c = match char_at(index) {
+                Some(c) => c,
+                None => {
+                    state = self.dfa.next_eoi_state(cache, state).unwrap();
+                    if state.is_match() {
+                        len = Some(index);
+                    }
+                    return len.filter(|&len| len > 0);
+                }
+            };
+            for (i, &byte) in c.encode_utf8(&mut utf8).as_bytes().iter().enumerate() {
+                state = self.dfa.next_state(cache, state, byte).unwrap();
+                // matches are reported one byte late, they only end between `char`s
+                if i == 0 && state.is_match() {
+                    len = Some(index);
+                }
+                if state.is_dead() {
+                    return len.filter(|&len| len > 0);
+                }
+            }
+        }
+        len.filter(|&len| len > 0)
+    }
+}
+
+impl Clone for RegexPattern {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            dfa: self.dfa.clone(),
+            cache: Mutex::new(self.dfa.create_cache()),
+        }
+    }
+}
+
+impl PartialEq for RegexPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for RegexPattern {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RegexPattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RegexPattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let re = <String as serde::Deserialize>::deserialize(deserializer)?;
+        RegexPattern::new(&re).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Deserializes regular expressions from either a single string or a list of alternatives.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_regexes<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<RegexPattern>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let res = match serde::Deserialize::deserialize(deserializer)? {
+        OneOrMany::One(re) => vec![re],
+        OneOrMany::Many(res) => res,
+    };
+    res.iter()
+        .map(|re| RegexPattern::new(re).map_err(serde::de::Error::custom))
+        .collect()
+}
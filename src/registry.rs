@@ -0,0 +1,205 @@
+use crate::detect;
+use crate::languages::{Language, UnknownLanguage};
+use crate::without_comments::Comment;
+use std::path::Path;
+
+/// A named language definition, either one of the built-in `Language`s or one registered by
+/// the application at runtime.
+///
+/// With the `serde` feature, definitions can be loaded from data files, `aliases` and
+/// `extensions` are optional.
+///
+/// ```toml
+/// name = "Lisp"
+/// aliases = ["scheme"]
+/// extensions = ["lisp", "scm"]
+/// comments = [
+///     { open_pat = ";", close_pats = ["\r\n", "\n", "\r"], keep_close_pat = true, allow_close_pat = true },
+///     { open_pat = "#|", close_pat = "|#", nests = true },
+///     { open_pat = "\"", close_pat = "\"", literal = true, escape = "\\" },
+/// ]
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Definition {
+    /// Human-readable name of the language, it is also used for lookups
+    pub name: String,
+    /// Other names that the language can be looked up by
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub aliases: Vec<String>,
+    /// Extensions (without the leading dot) of the files in this language, the built-in
+    /// languages leave this empty and are found by `detect` instead
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub extensions: Vec<String>,
+    /// Comment specifications, as consumed by `without_comments`
+    pub comments: Box<[Comment]>,
+}
+
+impl Definition {
+    /// Creates a definition without aliases.
+    pub fn new(name: impl Into<String>, comments: Box<[Comment]>) -> Self {
+        Self {
+            name: name.into(),
+            aliases: Vec::new(),
+            extensions: Vec::new(),
+            comments,
+        }
+    }
+
+    /// Adds an alias to the definition.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    /// Adds a file extension (without the leading dot) to the definition.
+    pub fn extension(mut self, extension: impl Into<String>) -> Self {
+        self.extensions.push(extension.into());
+        self
+    }
+
+    /// Whether `name` is the name or one of the aliases of the definition, ignoring case.
+    fn is_called(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+}
+
+impl From<Language> for Definition {
+    fn from(language: Language) -> Self {
+        Self {
+            name: language.display_name().to_string(),
+            aliases: std::iter::once(language.name())
+                .chain(language.aliases().iter().copied())
+                .map(str::to_string)
+                .collect(),
+            extensions: Vec::new(),
+            comments: language.comments(),
+        }
+    }
+}
+
+/// A set of language definitions that can be looked up by name or alias (case-insensitive).
+/// A new registry contains all of the built-in languages, applications can add their own
+/// definitions with `register`.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::registry::{Definition, Registry};
+/// use no_comment::{Comment, IntoWithoutComments as _};
+///
+/// let mut registry = Registry::new();
+/// registry.register(
+///     Definition::new("Lisp", vec![Comment::line(";")].into_boxed_slice()).alias("scheme"),
+/// );
+///
+/// let lisp = registry.get("Scheme").unwrap();
+/// let without_comments = "(+ 1 2) ; three"
+///     .chars()
+///     .without_comments(lisp.comments.clone())
+///     .collect::<String>();
+/// assert_eq!(&without_comments, "(+ 1 2) ");
+///
+/// assert_eq!(registry.get("cpp").unwrap().name, "C");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Registry {
+    /// Definitions in order of registration, later definitions take precedence
+    definitions: Vec<Definition>,
+}
+
+impl Registry {
+    /// Creates a registry containing all of the built-in languages.
+    pub fn new() -> Self {
+        Self {
+            definitions: Language::iter().map(Definition::from).collect(),
+        }
+    }
+
+    /// Creates a registry without any definitions.
+    pub fn empty() -> Self {
+        Self {
+            definitions: Vec::new(),
+        }
+    }
+
+    /// Adds a definition to the registry. A previous definition with the same name is replaced,
+    /// if the name or an alias is shared with other definitions, the newest one is returned by
+    /// lookups.
+    pub fn register(&mut self, definition: Definition) {
+        self.definitions
+            .retain(|existing| !existing.name.eq_ignore_ascii_case(&definition.name));
+        self.definitions.push(definition);
+    }
+
+    /// Looks up a definition by name or alias, ignoring case.
+    pub fn get(&self, name: &str) -> Option<&Definition> {
+        self.definitions
+            .iter()
+            .rev()
+            .find(|definition| definition.is_called(name))
+    }
+
+    /// Finds the definition of a file's language. Definitions listing the file's extension
+    /// are tried first (the newest one wins), then the language is detected with
+    /// `detect::detect` and looked up by name, so registering a definition named like a
+    /// built-in language overrides it for detected files too.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::registry::{Definition, Registry};
+    /// use no_comment::Comment;
+    ///
+    /// let mut registry = Registry::new();
+    /// registry.register(
+    ///     Definition::new("Lisp", vec![Comment::line(";")].into_boxed_slice()).extension("el"),
+    /// );
+    /// assert_eq!(registry.detect("init.el", "").unwrap().name, "Lisp");
+    /// assert_eq!(registry.detect("main.rs", "").unwrap().name, "Rust");
+    /// ```
+    pub fn detect(&self, path: impl AsRef<Path>, text: &str) -> Option<&Definition> {
+        let path = path.as_ref();
+        let by_extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| {
+                self.definitions.iter().rev().find(|definition| {
+                    definition
+                        .extensions
+                        .iter()
+                        .any(|extension| extension.eq_ignore_ascii_case(ext))
+                })
+            });
+        by_extension
+            .or_else(|| detect::detect(path, text).and_then(|language| self.get(language.name())))
+    }
+
+    /// Same as `get` but returns the comment specifications directly, or an error naming the
+    /// unknown language.
+    pub fn comments(&self, name: &str) -> Result<Box<[Comment]>, UnknownLanguage> {
+        self.get(name)
+            .map(|definition| definition.comments.clone())
+            .ok_or_else(|| UnknownLanguage(name.to_string()))
+    }
+
+    /// Iterate over all definitions in order of registration, starting with the built-in
+    /// languages.
+    pub fn iter(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions.iter()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
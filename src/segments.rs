@@ -0,0 +1,113 @@
+use crate::error::Error;
+use crate::position::Position;
+use crate::without_comments::{Class, WithoutComments};
+
+/// Whether a `Segment` is regular text or a comment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SegmentKind {
+    Text,
+    Comment,
+}
+
+/// A piece of the input that is either regular text or a single (top-level) comment, including
+/// its open and close patterns. Close patterns that are kept (such as the `\n` ending a line
+/// comment) are part of the following text segment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Segment {
+    /// Whether this is text or a comment
+    pub kind: SegmentKind,
+    /// The `char`s of the input making up the segment
+    pub text: String,
+    /// Position of the first `char` of the segment
+    pub start: Position,
+    /// Position right after the last `char` of the segment
+    pub end: Position,
+}
+
+impl Segment {
+    /// Whether the segment is a comment.
+    pub fn is_comment(&self) -> bool {
+        self.kind == SegmentKind::Comment
+    }
+}
+
+/// Iterator splitting its input into alternating `Segment`s of text and comments using the same
+/// rules as `WithoutComments`. Consecutive comments (`/* a *//* b */`) are separate segments
+/// while nested comments are part of the outermost one. Returns an error if `WithoutComments`
+/// would panic, after which iteration stops.
+pub struct Segments<I: Iterator<Item = char>> {
+    /// Inner iterator, used for its classification of `char`s
+    inner: WithoutComments<I>,
+    /// The next classified `char`, already read from `inner`
+    peeked: Option<(char, Position, Class)>,
+    /// Whether an error has been returned
+    failed: bool,
+}
+
+impl<I: Iterator<Item = char>> Segments<I> {
+    pub(crate) fn new(inner: WithoutComments<I>) -> Self {
+        Self {
+            inner,
+            peeked: None,
+            failed: false,
+        }
+    }
+
+    fn next_classified(&mut self) -> Result<Option<(char, Position, Class)>, Error> {
+        match self.peeked.take() {
+            Some(item) => Ok(Some(item)),
+            None => self.inner.next_classified(),
+        }
+    }
+
+    fn next_segment(&mut self) -> Result<Option<Segment>, Error> {
+        let (c, start, class) = match self.next_classified()? {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+
+        let kind = match class {
+            Class::Text | Class::Literal => SegmentKind::Text,
+            Class::CommentStart | Class::Comment => SegmentKind::Comment,
+        };
+        let mut text = c.to_string();
+        // `inner` is always right after the last `char` it returned
+        let mut end = self.inner.position();
+
+        while let Some((c, position, class)) = self.next_classified()? {
+            let continues = match kind {
+                SegmentKind::Text => class.is_text(),
+                SegmentKind::Comment => class == Class::Comment,
+            };
+            if !continues {
+                self.peeked = Some((c, position, class));
+                break;
+            }
+            text.push(c);
+            end = self.inner.position();
+        }
+
+        Ok(Some(Segment {
+            kind,
+            text,
+            start,
+            end,
+        }))
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for Segments<I> {
+    type Item = Result<Segment, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.next_segment();
+        self.failed = result.is_err();
+        result.transpose()
+    }
+}
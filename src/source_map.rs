@@ -0,0 +1,168 @@
+use crate::error::Error;
+use crate::position::{ends_line, is_line_terminator};
+use crate::without_comments::{Comment, IntoWithoutComments as _};
+
+/// A location in a text, either the original text or the text without comments.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Location {
+    /// Offset in bytes from the beginning of the text, as if it were UTF-8 encoded
+    pub offset: usize,
+    /// Line number, starting at 1
+    pub line: usize,
+}
+
+/// A stretch of text that was kept as is, on a single line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Run {
+    /// Location of the first `char` of the run in the original text
+    original: Location,
+    /// Location of the first `char` of the run in the text without comments
+    stripped: Location,
+    /// Length of the run in bytes
+    len: usize,
+}
+
+/// Mapping between the locations of a text and those of the same text without comments, built
+/// by `strip`. The map is run-length encoded, it stores one entry per stretch of text that
+/// isn't interrupted by a comment or a newline, so it's about as long as the number of lines
+/// plus the number of comments.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::languages;
+/// use no_comment::source_map::{strip, Location};
+///
+/// let (stripped, map) = strip("a /* b */c\nd".chars(), languages::c()).unwrap();
+/// assert_eq!(stripped, "a c\nd");
+///
+/// // `c` is at offset 2 in the stripped text and at offset 9 in the original one
+/// assert_eq!(map.original(2), Some(Location { offset: 9, line: 1 }));
+/// assert_eq!(map.stripped(9), Some(Location { offset: 2, line: 1 }));
+/// // `b` was removed
+/// assert_eq!(map.stripped(5), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SourceMap {
+    /// Runs in order, they are contiguous in the stripped text
+    runs: Vec<Run>,
+    /// Location right after the end of the original text
+    original_end: Location,
+    /// Location right after the end of the stripped text
+    stripped_end: Location,
+}
+
+impl SourceMap {
+    /// Maps an offset in the text without comments to a location in the original text. The
+    /// end of the stripped text maps to the end of the original text. Returns `None` if
+    /// `offset` is past the end of the stripped text.
+    pub fn original(&self, offset: usize) -> Option<Location> {
+        if offset == self.stripped_end.offset {
+            return Some(self.original_end);
+        }
+        let idx = self
+            .runs
+            .partition_point(|run| run.stripped.offset <= offset);
+        let run = self.runs[..idx].last()?;
+        if offset >= run.stripped.offset + run.len {
+            return None;
+        }
+        Some(Location {
+            offset: run.original.offset + (offset - run.stripped.offset),
+            line: run.original.line,
+        })
+    }
+
+    /// Maps an offset in the original text to a location in the text without comments. The
+    /// end of the original text maps to the end of the stripped text. Returns `None` if
+    /// `offset` is part of a comment (including its open and close patterns, unless the close
+    /// pattern is kept) or past the end of the original text.
+    pub fn stripped(&self, offset: usize) -> Option<Location> {
+        if offset == self.original_end.offset {
+            return Some(self.stripped_end);
+        }
+        let idx = self
+            .runs
+            .partition_point(|run| run.original.offset <= offset);
+        let run = self.runs[..idx].last()?;
+        if offset >= run.original.offset + run.len {
+            return None;
+        }
+        Some(Location {
+            offset: run.stripped.offset + (offset - run.original.offset),
+            line: run.stripped.line,
+        })
+    }
+
+    /// Number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Whether the map has no entries, which is the case when the stripped text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+}
+
+/// Removes the comments from `text` like `WithoutComments` does, also returning the
+/// `SourceMap` between the original and the stripped text. Returns an error if
+/// `WithoutComments` would panic.
+pub fn strip(
+    text: impl Iterator<Item = char>,
+    language: Box<[Comment]>,
+) -> Result<(String, SourceMap), Error> {
+    let mut iter = text.without_comments(language);
+
+    let mut stripped = String::new();
+    let mut stripped_line = 1;
+    let mut runs = Vec::<Run>::new();
+    // whether the next text `char` can be added to the last run
+    let mut contiguous = false;
+    while let Some((c, position, class)) = iter.next_classified()? {
+        if !class.is_text() {
+            contiguous = false;
+            continue;
+        }
+
+        match runs.last_mut() {
+            Some(run) if contiguous => run.len += c.len_utf8(),
+            _ => runs.push(Run {
+                original: Location {
+                    offset: position.offset,
+                    line: position.line,
+                },
+                stripped: Location {
+                    offset: stripped.len(),
+                    line: stripped_line,
+                },
+                len: c.len_utf8(),
+            }),
+        }
+
+        // runs stop at line terminators, so each of them is on a single line
+        let ends_line = ends_line(stripped.chars().next_back(), c);
+        stripped.push(c);
+        contiguous = !is_line_terminator(c);
+        if ends_line {
+            stripped_line += 1;
+        }
+    }
+
+    let end = iter.position();
+    let map = SourceMap {
+        runs,
+        original_end: Location {
+            offset: end.offset,
+            line: end.line,
+        },
+        stripped_end: Location {
+            offset: stripped.len(),
+            line: stripped_line,
+        },
+    };
+    Ok((stripped, map))
+}
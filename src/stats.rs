@@ -0,0 +1,121 @@
+use crate::error::Error;
+use crate::position::{ends_line, is_line_terminator};
+use crate::without_comments::{Class, Comment, IntoWithoutComments as _};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+/// Line counts of a text, in the style of `cloc`. Every line falls in exactly one category:
+/// a line is blank if it contains only whitespace (even inside a comment), otherwise it is
+/// code, comment or mixed depending on whether its non-whitespace `char`s are text, comments
+/// or both.
+///
+/// # Example
+///
+/// ```
+/// use no_comment::{languages, stats::LineStats};
+/// let text = "fn main() {\n    // hi\n\n    f(); /* call */\n}\n";
+/// let stats = LineStats::count(text.chars(), languages::rust()).unwrap();
+/// assert_eq!(
+///     stats,
+///     LineStats {
+///         code: 2,
+///         comment: 1,
+///         mixed: 1,
+///         blank: 1,
+///     }
+/// );
+/// assert_eq!(stats.total(), 5);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LineStats {
+    /// Lines containing only text
+    pub code: usize,
+    /// Lines containing only comments
+    pub comment: usize,
+    /// Lines containing both text and comments
+    pub mixed: usize,
+    /// Lines containing only whitespace
+    pub blank: usize,
+}
+
+impl LineStats {
+    /// Counts the lines of `text` using the same rules as `WithoutComments`. A trailing line
+    /// terminator doesn't start a new line.
+    pub fn count(
+        text: impl Iterator<Item = char>,
+        language: Box<[Comment]>,
+    ) -> Result<Self, Error> {
+        let mut stats = Self::default();
+        let mut iter = text.without_comments(language);
+
+        // whether the current line has non-whitespace text and comment chars, and whether it
+        // has any char at all
+        let (mut has_code, mut has_comment, mut started) = (false, false, false);
+        let mut last = None;
+        while let Some((c, _, class)) = iter.next_classified()? {
+            let prev = last.replace(c);
+            if is_line_terminator(c) {
+                if ends_line(prev, c) {
+                    stats.add_line(has_code, has_comment);
+                    has_code = false;
+                    has_comment = false;
+                    started = false;
+                }
+                continue;
+            }
+
+            started = true;
+            if !c.is_whitespace() {
+                match class {
+                    Class::Text | Class::Literal => has_code = true,
+                    Class::CommentStart | Class::Comment => has_comment = true,
+                }
+            }
+        }
+        if started {
+            stats.add_line(has_code, has_comment);
+        }
+
+        Ok(stats)
+    }
+
+    /// Total number of lines.
+    pub fn total(&self) -> usize {
+        self.code + self.comment + self.mixed + self.blank
+    }
+
+    fn add_line(&mut self, has_code: bool, has_comment: bool) {
+        match (has_code, has_comment) {
+            (true, true) => self.mixed += 1,
+            (true, false) => self.code += 1,
+            (false, true) => self.comment += 1,
+            (false, false) => self.blank += 1,
+        }
+    }
+}
+
+impl Add for LineStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            code: self.code + other.code,
+            comment: self.comment + other.comment,
+            mixed: self.mixed + other.mixed,
+            blank: self.blank + other.blank,
+        }
+    }
+}
+
+impl AddAssign for LineStats {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sum for LineStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
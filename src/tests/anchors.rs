@@ -0,0 +1,128 @@
+use super::strip;
+use crate::validation::{validate, ValidationError};
+use crate::{Anchor, Comment, IntoWithoutComments as _};
+
+#[test]
+fn test_ini() {
+    let ini = [Comment::line(";")
+        .alt_open_pat("#")
+        .open_anchor(Anchor::Indented)];
+    assert_eq!(
+        strip("; top\n[a]\n  # indented\nb = c ; d # e\n", &ini),
+        "\n[a]\n  \nb = c ; d # e\n"
+    );
+    assert_eq!(strip("a\r\n; b\r\n\t; c", &ini), "a\r\n\r\n\t");
+}
+
+#[test]
+fn test_fortran_fixed_form() {
+    let fortran = [Comment::line("C")
+        .alt_open_pat("c")
+        .alt_open_pat("*")
+        .open_anchor(Anchor::LineStart)];
+    assert_eq!(
+        strip("C comment\n      X = A * B\n c not one\n*\n", &fortran),
+        "\n      X = A * B\n c not one\n\n"
+    );
+}
+
+#[test]
+fn test_ruby_and_perl_blocks() {
+    let ruby = vec![
+        Comment::line("#"),
+        Comment::block("=begin", "=end")
+            .open_anchor(Anchor::LineStart)
+            .close_anchor(Anchor::LineStart),
+    ];
+    assert_eq!(
+        strip("a = 1\n=begin\n  =end\nx =end\n=end\nb = 2 # two\n", &ruby),
+        "a = 1\n\nb = 2 \n"
+    );
+
+    let perl = vec![
+        Comment::line("#"),
+        Comment::block("=pod", "=cut")
+            .alt_open_pat("=head1")
+            .open_anchor(Anchor::LineStart)
+            .close_anchor(Anchor::LineStart),
+    ];
+    assert_eq!(
+        strip("my $x = 1;\n=head1 NAME\n\nx\n=cut\nprint $x;\n", &perl),
+        "my $x = 1;\n\nprint $x;\n"
+    );
+}
+
+#[test]
+fn test_dockerfile() {
+    let dockerfile = [Comment::line("#").open_anchor(Anchor::Indented)];
+    assert_eq!(
+        strip(
+            "# syntax\nFROM x\nRUN echo '#' \\\n  # continued\n",
+            &dockerfile
+        ),
+        "\nFROM x\nRUN echo '#' \\\n  \n"
+    );
+}
+
+#[test]
+fn test_anchored_close_pattern_outside_comment() {
+    // an anchored close pattern elsewhere in a line isn't a close pattern
+    let language = vec![Comment::block("=begin", "=end").close_anchor(Anchor::LineStart)];
+    let text = "a =end b";
+    assert_eq!(strip(text, &language), text);
+    assert!("a\n=end"
+        .chars()
+        .without_comments(language.into_boxed_slice())
+        .try_collect::<String>()
+        .is_err());
+}
+
+#[test]
+fn test_anchored_validation() {
+    // an anchored pattern doesn't shadow the same pattern anywhere else
+    let language = [
+        Comment::line("#").open_anchor(Anchor::LineStart),
+        Comment::block("#", "#"),
+    ];
+    assert_eq!(validate(&language), Ok(()));
+    let language = [
+        Comment::line("#").open_anchor(Anchor::Indented),
+        Comment::block("#", "#").open_anchor(Anchor::LineStart),
+    ];
+    assert_eq!(
+        validate(&language),
+        Err(ValidationError::ShadowedOpenPattern { index: 1, by: 0 })
+    );
+
+    // the start of the input is also the start of a line
+    let language = [
+        Comment::line("#!").open_anchor(Anchor::LineStart),
+        Comment::line("#!").open_anchor(Anchor::FileStart),
+    ];
+    assert_eq!(
+        validate(&language),
+        Err(ValidationError::ShadowedOpenPattern { index: 1, by: 0 })
+    );
+    assert_eq!(
+        strip(
+            "#! a
+#! b",
+            &language[1..]
+        ),
+        "
+#! b"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_anchor_deserialize() {
+    let json = r#"{ "open_pat": "=begin", "close_pat": "=end", "open_anchor": "line_start",
+        "close_anchor": "line_start" }"#;
+    assert_eq!(
+        serde_json::from_str::<Comment>(json).unwrap(),
+        Comment::block("=begin", "=end")
+            .open_anchor(Anchor::LineStart)
+            .close_anchor(Anchor::LineStart)
+    );
+}
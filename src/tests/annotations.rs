@@ -0,0 +1,90 @@
+use crate::annotations::{annotations, Annotation, DEFAULT_TAGS};
+use crate::languages::{c, python, rust};
+use crate::Position;
+
+/// Tag, owner, issue, text, line and column of an annotation.
+type Summary<'a> = (
+    &'a str,
+    Option<&'a str>,
+    Option<&'a str>,
+    &'a str,
+    usize,
+    usize,
+);
+
+fn summary(found: &[Annotation]) -> Vec<Summary<'_>> {
+    found
+        .iter()
+        .map(|a| {
+            (
+                a.tag.as_str(),
+                a.owner.as_deref(),
+                a.issue.as_deref(),
+                a.text.as_str(),
+                a.position.line,
+                a.position.column,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_annotations() {
+    let text = "\
+fn main() { // TODO: write main
+    let s = \"TODO: not in a comment\";
+    /* FIXME(alice): #12 leaks */
+    // XXX
+    // HACK(bob) works around #3
+    // TODOS are not tags, neither is NOTTODO or TODO_LATER
+}
+";
+    let found = annotations(text.chars(), rust(), &DEFAULT_TAGS).unwrap();
+    assert_eq!(
+        summary(&found),
+        [
+            ("TODO", None, None, "write main", 1, 16),
+            ("FIXME", Some("alice"), Some("12"), "leaks", 3, 8),
+            ("XXX", None, None, "", 4, 8),
+            ("HACK", Some("bob"), None, "works around #3", 5, 8),
+        ]
+    );
+}
+
+#[test]
+fn test_annotations_multiline_comment() {
+    let text = "x = 1\n'''\nDocs.\n\nTODO: more docs\n'''\n";
+    let found = annotations(text.chars(), python(), &DEFAULT_TAGS).unwrap();
+    assert_eq!(
+        found,
+        [Annotation {
+            tag: "TODO".to_string(),
+            owner: None,
+            issue: None,
+            text: "more docs".to_string(),
+            position: Position {
+                offset: 17,
+                line: 5,
+                column: 1,
+            },
+        }]
+    );
+}
+
+#[test]
+fn test_annotations_custom_tags() {
+    let text = "int x; /* NOTE: x */ /* TODO: y */\n// BUG #7: z\n";
+    let found = annotations(text.chars(), c(), &["NOTE", "BUG"]).unwrap();
+    assert_eq!(
+        summary(&found),
+        [
+            ("NOTE", None, None, "x", 1, 11),
+            ("BUG", None, Some("7"), "z", 2, 4),
+        ]
+    );
+}
+
+#[test]
+fn test_annotations_error() {
+    assert!(annotations("// TODO\n*/".chars(), rust(), &DEFAULT_TAGS).is_err());
+}
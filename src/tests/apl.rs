@@ -0,0 +1,63 @@
+use super::strip;
+use crate::languages::apl;
+use crate::{Comment, IntoWithoutComments as _};
+
+#[test]
+fn test_line_comments() {
+    let strings = [
+        ("⍝ a comment", ""),
+        ("x←1 ⍝ one", "x←1 "),
+        ("x←1⍝one\ny←2⍝two\n", "x←1\ny←2\n"),
+        ("⍝⍝ double\n⍝\n", "\n\n"),
+        ("+/⍳10", "+/⍳10"),
+        ("s←'⍝ not a comment' ⍝ a comment", "s←'⍝ not a comment' "),
+        ("s←'it''s ⍝ here' ⍝ x", "s←'it''s ⍝ here' "),
+        ("s←\"⍝\"⍝", "s←\"⍝\""),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &apl()), expected, "{:?}", string);
+    }
+}
+
+#[test]
+fn test_multi_byte_patterns() {
+    // patterns of several chars that are several bytes long each
+    let language = vec![
+        Comment::block("«⟦", "⟧»").nests(true),
+        Comment::line("｜｜"),
+        Comment::literal("「", "」"),
+    ]
+    .into_boxed_slice();
+    let strings = [
+        ("a«⟦b⟧»c", "ac"),
+        ("a«⟦b«⟦c⟧»d⟧»e", "ae"),
+        ("a ｜｜ b\nc", "a \nc"),
+        ("a ｜ b ｜", "a ｜ b ｜"),
+        ("a「｜｜」b", "a「｜｜」b"),
+        ("«⟦unclosed ⟧", ""),
+        ("«⟦a⟧»«⟦b⟧»x", "x"),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &language), expected, "{:?}", string);
+    }
+}
+
+#[test]
+fn test_multi_byte_positions() {
+    let segments = "x ⍝ é\ny"
+        .chars()
+        .segments(apl())
+        .map(Result::unwrap)
+        .map(|segment| (segment.start.column, segment.end.offset, segment.text))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        segments,
+        [
+            (1, 2, "x ".to_string()),
+            (3, 8, "⍝ é".to_string()),
+            (6, 10, "\ny".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,21 @@
+use super::strip;
+use crate::languages::batch;
+
+#[test]
+fn test_comments() {
+    let strings = [
+        ("REM a comment\r\necho hi\r\n", "\r\necho hi\r\n"),
+        ("rem lower\nRem mixed\n@rem quiet\n", "\n\n\n"),
+        ("  REM indented\n", "  \n"),
+        ("REM.\nREM:x\n", "\n\n"),
+        ("REMARK\nset REM=1\n", "REMARK\nset REM=1\n"),
+        ("echo REM not a comment\n", "echo REM not a comment\n"),
+        (":: label comment\n:label\n", "\n:label\n"),
+        ("echo a :: b\n", "echo a :: b\n"),
+        ("REM", ""),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &batch()), expected, "{:?}", string);
+    }
+}
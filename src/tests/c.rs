@@ -125,3 +125,19 @@ fn test_block_and_line_together() {
         assert_eq!(&without_comments, check);
     }
 }
+
+#[test]
+fn test_crlf_line_endings() {
+    // the whole line terminator is kept, a CRLF file stays a CRLF file
+    let strings = [
+        ("a // x\r\nb\r\n", "a \r\nb\r\n"),
+        ("a /* x\r\ny */ b\r\n// c\r\n", "a  b\r\n\r\n"),
+        ("// a\r\n// b\r\n\r\nc", "\r\n\r\n\r\nc"),
+    ];
+
+    for (string, check) in strings.iter() {
+        let without_comments = string.chars().without_comments(c()).collect::<String>();
+
+        assert_eq!(&without_comments, check);
+    }
+}
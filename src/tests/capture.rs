@@ -0,0 +1,127 @@
+use super::strip;
+use crate::capture::DEFAULT_MAX_LEN;
+use crate::validation::{validate, ValidationError};
+use crate::{Anchor, Capture, Comment};
+
+fn cpp() -> Vec<Comment> {
+    vec![
+        Comment::line("//"),
+        Comment::block("/*", "*/"),
+        Comment::literal("\"", "\"").escape('\\'),
+        Comment::literal("R\"", ")").capture(Capture::none_of(" ()\\\t\n", "(").close_suffix("\"")),
+    ]
+}
+
+#[test]
+fn test_cpp_raw_strings() {
+    assert_eq!(
+        strip(r#"s = R"(a // b)"; // c"#, &cpp()),
+        r#"s = R"(a // b)"; "#
+    );
+    assert_eq!(
+        strip("s = R\"sql(\n/* x */ \")\" )sql\"; /* c */", &cpp()),
+        "s = R\"sql(\n/* x */ \")\" )sql\"; "
+    );
+    // a raw string isn't closed by a different delimiter
+    assert_eq!(
+        strip(r#"R"a( )b" // )a" // c"#, &cpp()),
+        r#"R"a( )b" // )a" "#
+    );
+}
+
+#[test]
+fn test_capture_must_end() {
+    // without the `(`, `R"` isn't a raw string and the quote opens a regular string
+    assert_eq!(strip(r#"R"a b" // c"#, &cpp()), r#"R"a b" "#);
+
+    // delimiters longer than the maximum length aren't captured, leaving a regular string
+    let delimiter = "x".repeat(DEFAULT_MAX_LEN + 1);
+    let text = format!("R\"{}( )\" // a\n", delimiter);
+    assert_eq!(strip(&text, &cpp()), format!("R\"{}( )\" \n", delimiter));
+}
+
+#[test]
+fn test_heredocs() {
+    let shell = [
+        Comment::line("#"),
+        Comment::literal("<<", "")
+            .capture(Capture::none_of(" \t\r\n<>|&;()'\"", "").min_len(1))
+            .close_anchor(Anchor::LineStart),
+        Comment::literal("<<'", "")
+            .capture(Capture::none_of("'\n", "'").min_len(1))
+            .close_anchor(Anchor::LineStart),
+    ];
+    assert_eq!(
+        strip("cat <<EOF\n# kept\n EOF\nEOF\necho # c\n", &shell),
+        "cat <<EOF\n# kept\n EOF\nEOF\necho \n"
+    );
+    assert_eq!(
+        strip("cat <<'END'\n# kept\nEND\n# c\n", &shell),
+        "cat <<'END'\n# kept\nEND\n\n"
+    );
+    // `<<` without a word isn't a heredoc
+    assert_eq!(strip("echo $((1 << 2)) # c", &shell), "echo $((1 << 2)) ");
+}
+
+#[test]
+fn test_capture_min_len() {
+    let language =
+        vec![Comment::block("#", "}").capture(Capture::one_of("0123456789", "{").min_len(1))];
+    // the close pattern without the captured part is allowed in regular text
+    assert_eq!(strip("a #{ b } #1{ c }2 }1 d", &language), "a #{ b }  d");
+}
+
+#[test]
+fn test_capture_validation() {
+    let language = [Comment::block("[", "]")
+        .capture(Capture::one_of("=", "["))
+        .nests(true)];
+    let err = validate(&language).unwrap_err();
+    assert_eq!(err, ValidationError::NestingCapture { index: 0 });
+    assert_eq!(
+        err.to_string(),
+        "comments[0]: a comment with a capture can't nest"
+    );
+
+    let language =
+        [Comment::block("[", "]").capture(Capture::one_of("=", "[").min_len(3).max_len(2))];
+    assert_eq!(
+        validate(&language),
+        Err(ValidationError::UnreachableOpenPattern { index: 0 })
+    );
+
+    // the close pattern can be empty if the captured part can't be
+    let heredoc = Comment::literal("<<", "").capture(Capture::none_of(" \n", ""));
+    assert_eq!(
+        validate(std::slice::from_ref(&heredoc)),
+        Err(ValidationError::EmptyClosePattern { index: 0 })
+    );
+    assert_eq!(
+        validate(&[heredoc.capture(Capture::none_of(" \n", "").min_len(1))]),
+        Ok(())
+    );
+
+    // an open pattern with a capture doesn't shadow anything
+    let language = [
+        Comment::block("--[", "]")
+            .capture(Capture::one_of("=", "["))
+            .priority(1),
+        Comment::line("--[x"),
+    ];
+    assert_eq!(validate(&language), Ok(()));
+    assert_eq!(validate(&crate::languages::lua()), Ok(()));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_capture_deserialize() {
+    let json = r#"{
+        "open_pat": "--[",
+        "close_pat": "]",
+        "capture": { "chars": { "one_of": "=" }, "end": "[", "close_suffix": "]" }
+    }"#;
+    assert_eq!(
+        serde_json::from_str::<Comment>(json).unwrap(),
+        Comment::block("--[", "]").capture(Capture::one_of("=", "[").close_suffix("]"))
+    );
+}
@@ -0,0 +1,84 @@
+use super::strip;
+use crate::validation::{validate, ValidationError};
+use crate::{Anchor, CharClass, Comment, IntoWithoutComments as _};
+
+#[test]
+fn test_ascii() {
+    let basic = [Comment::line("REM")
+        .preceded_by(CharClass::NonWord)
+        .followed_by(CharClass::NonWord)
+        .ignore_case(true)];
+    assert_eq!(
+        strip("10 REM a\n20 rem b\n30 ReM c\n40 PRINT 1\n", &basic),
+        "10 \n20 \n30 \n40 PRINT 1\n"
+    );
+
+    let fortran = [
+        Comment::line("c")
+            .alt_open_pat("*")
+            .open_anchor(Anchor::LineStart)
+            .ignore_case(true),
+        Comment::line("!"),
+    ];
+    assert_eq!(
+        strip("C one\nc two\n      X = 1 ! three\n", &fortran),
+        "\n\n      X = 1 \n"
+    );
+}
+
+#[test]
+fn test_close_patterns() {
+    let language = vec![Comment::block("/*", "END COMMENT").ignore_case(true)];
+    assert_eq!(strip("a /* b End Comment c", &language), "a  c");
+    assert!("a end comment"
+        .chars()
+        .without_comments(language.into_boxed_slice())
+        .try_collect::<String>()
+        .is_err());
+}
+
+#[test]
+fn test_unicode_case_folding() {
+    let language = [Comment::line("ΣΧΌΛΙΟ").ignore_case(true)];
+    // final and non-final sigma both fold to the same char
+    assert_eq!(strip("a σχόλιο b\nc ςχόλιο d\ne", &language), "a \nc \ne");
+
+    let language = [Comment::block("straße", "ende").ignore_case(true)];
+    assert_eq!(strip("a STRAẞE b ENDE c", &language), "a  c");
+    // folding is done one char at a time, `ß` doesn't match `ss`
+    assert_eq!(strip("a STRASSE b", &language), "a STRASSE b");
+
+    // the Kelvin sign folds to `k`
+    let language = vec![Comment::line("k:").ignore_case(true)];
+    assert_eq!(strip("a \u{212A}: b", &language), "a ");
+}
+
+#[test]
+fn test_case_sensitive_by_default() {
+    let language = vec![Comment::line("REM")];
+    assert_eq!(strip("rem a\nREM b", &language), "rem a\n");
+}
+
+#[test]
+fn test_ignore_case_validation() {
+    // a case-sensitive pattern doesn't shadow a case-insensitive one
+    let language = [
+        Comment::line("rem").priority(1),
+        Comment::line("REM").ignore_case(true),
+    ];
+    assert_eq!(validate(&language), Ok(()));
+    let language = [Comment::line("rem").ignore_case(true), Comment::line("REM")];
+    assert_eq!(
+        validate(&language),
+        Err(ValidationError::ShadowedOpenPattern { index: 1, by: 0 })
+    );
+    assert_eq!(
+        validate(&[Comment::block("{", "{").nests(true)]),
+        Err(ValidationError::AmbiguousNesting { index: 0 })
+    );
+    assert_eq!(
+        validate(&[Comment::block("(A", "(a").nests(true).ignore_case(true)]),
+        Err(ValidationError::AmbiguousNesting { index: 0 })
+    );
+    assert_eq!(validate(&[Comment::block("(A", "(a").nests(true)]), Ok(()));
+}
@@ -0,0 +1,81 @@
+use super::strip;
+use crate::validation::{validate, ValidationError};
+use crate::{CharClass, Comment};
+
+#[test]
+fn test_preceded_by_whitespace() {
+    let yaml = [
+        Comment::line("#").preceded_by(CharClass::Whitespace),
+        Comment::literal("\"", "\"").escape('\\'),
+        Comment::literal("'", "'"),
+    ];
+    assert_eq!(
+        strip("# top\nurl: http://x/#anchor # link\nc: \"#\"\n", &yaml),
+        "\nurl: http://x/#anchor \nc: \"#\"\n"
+    );
+    // the start of the input counts as whitespace
+    assert_eq!(strip("#a", &yaml), "");
+}
+
+#[test]
+fn test_whole_word() {
+    let basic = [Comment::line("REM")
+        .alt_open_pat("'")
+        .preceded_by(CharClass::NonWord)
+        .followed_by(CharClass::NonWord)];
+    assert_eq!(
+        strip(
+            "10 REM hello\n20 PREMIUM = 1\n30 X = 2:REM\n40 REMARK\n",
+            &basic
+        ),
+        "10 \n20 PREMIUM = 1\n30 X = 2:\n40 REMARK\n"
+    );
+    assert_eq!(strip("REM_X = 1 ' note", &basic), "REM_X = 1 ");
+}
+
+#[test]
+fn test_followed_by_at_end_of_input() {
+    // the `char` following the longest pattern is read ahead
+    let language = vec![
+        Comment::block("<<", ">>").followed_by(CharClass::Whitespace),
+        Comment::line("!!!!"),
+    ];
+    assert_eq!(strip("a << b >> c <<d", &language), "a  c <<d");
+    assert_eq!(strip("a <<", &language), "a ");
+}
+
+#[test]
+fn test_char_class_validation() {
+    // `RE` can't shadow `REM` when it must be followed by a non-word char
+    let language = [
+        Comment::line("RE")
+            .followed_by(CharClass::NonWord)
+            .priority(1),
+        Comment::line("REM"),
+    ];
+    assert_eq!(validate(&language), Ok(()));
+
+    let language = [
+        Comment::line("#"),
+        Comment::block("#", "#").preceded_by(CharClass::Whitespace),
+    ];
+    assert_eq!(
+        validate(&language),
+        Err(ValidationError::ShadowedOpenPattern { index: 1, by: 0 })
+    );
+    let language = [
+        Comment::line("#").preceded_by(CharClass::Whitespace),
+        Comment::block("#", "#"),
+    ];
+    assert_eq!(validate(&language), Ok(()));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_char_class_deserialize() {
+    let json = r##"{ "open_pat": "#", "close_pat": "\n", "preceded_by": "whitespace" }"##;
+    assert_eq!(
+        serde_json::from_str::<Comment>(json).unwrap().preceded_by,
+        CharClass::Whitespace
+    );
+}
@@ -0,0 +1,144 @@
+use crate::detect::{detect, from_modeline, from_path, from_shebang};
+use crate::languages::Language;
+
+#[test]
+fn test_from_path() {
+    let paths = [
+        ("main.rs", Some(Language::Rust)),
+        ("src/lib.rs", Some(Language::Rust)),
+        ("/abs/path/to/file.c", Some(Language::C)),
+        ("header.h", Some(Language::C)),
+        ("HEADER.H", Some(Language::C)),
+        ("class.cpp", Some(Language::C)),
+        ("class.hpp", Some(Language::C)),
+        ("Main.java", Some(Language::C)),
+        ("script.py", Some(Language::Python)),
+        ("stubs.pyi", Some(Language::Python)),
+        ("SConstruct", Some(Language::Python)),
+        ("Main.hs", Some(Language::Haskell)),
+        ("tsconfig.json", Some(Language::Json)),
+        ("settings.jsonc", Some(Language::Json)),
+        ("Stats.aplf", Some(Language::Apl)),
+        ("build.sh", Some(Language::Shell)),
+        ("paper.tex", Some(Language::Latex)),
+        ("BUILD.BAT", Some(Language::Batch)),
+        ("init.lua", Some(Language::Lua)),
+        ("/home/user/.bashrc", Some(Language::Shell)),
+        ("README", None),
+        ("notes.txt", None),
+        ("rs", None),
+        (".rs", None),
+        ("", None),
+    ];
+
+    for (path, check) in paths.iter() {
+        assert_eq!(&from_path(path), check, "wrong language for \"{}\"", path);
+    }
+}
+
+#[test]
+fn test_from_shebang() {
+    let texts = [
+        ("#!/usr/bin/env python3\nprint(1)", Some(Language::Python)),
+        ("#!/usr/bin/python\n", Some(Language::Python)),
+        ("#!/usr/local/bin/python3.8", Some(Language::Python)),
+        ("#! /usr/bin/env python", Some(Language::Python)),
+        ("#!/usr/bin/env -S python3 -u\n", Some(Language::Python)),
+        ("#!/usr/bin/env runghc\n", Some(Language::Haskell)),
+        ("#!/usr/bin/runhaskell\n", Some(Language::Haskell)),
+        ("#!/bin/sh\n", Some(Language::Shell)),
+        ("#!/usr/bin/env bash\n", Some(Language::Shell)),
+        ("#!/usr/bin/env lua5.4\n", Some(Language::Lua)),
+        ("#!/usr/bin/perl\n", None),
+        ("#!/usr/bin/env\n", None),
+        ("#!\n", None),
+        ("print(1)\n#!/usr/bin/env python3", None),
+        ("# !/usr/bin/env python3", None),
+        ("", None),
+    ];
+
+    for (text, check) in texts.iter() {
+        assert_eq!(&from_shebang(text), check, "wrong language for {:?}", text);
+    }
+}
+
+#[test]
+fn test_from_modeline() {
+    let texts = [
+        ("// vim: ft=rust\nfn main() {}", Some(Language::Rust)),
+        ("# vim: set filetype=python :\n", Some(Language::Python)),
+        ("/* vi: set ts=4 ft=c: */\n", Some(Language::C)),
+        ("-- vim:syntax=haskell\n", Some(Language::Haskell)),
+        ("// -*- mode: rust -*-\n", Some(Language::Rust)),
+        ("// -*- Mode: C++; tab-width: 4 -*-\n", Some(Language::C)),
+        ("# -*- python -*-\n", Some(Language::Python)),
+        ("-- -*- mode:haskell -*-", Some(Language::Haskell)),
+        ("# -*- coding: utf-8 -*-\n", None),
+        ("// vim: ts=4 sw=4\n", None),
+        ("// vim: ft=cobol\n", None),
+        ("no modeline here", None),
+        // the marker must start the line or follow whitespace
+        ("let regex: ft=python\n", None),
+        ("// nvim: ft=rust\n", None),
+        ("vim: ft=rust\n", Some(Language::Rust)),
+        ("\tex: ft=python\n", Some(Language::Python)),
+        ("", None),
+    ];
+
+    for (text, check) in texts.iter() {
+        assert_eq!(&from_modeline(text), check, "wrong language for {:?}", text);
+    }
+}
+
+#[test]
+fn test_from_modeline_position() {
+    let middle = "\n".repeat(20);
+
+    let first = format!("// vim: ft=rust{}", middle);
+    assert_eq!(from_modeline(&first), Some(Language::Rust));
+
+    let last = format!("{}// vim: ft=rust", middle);
+    assert_eq!(from_modeline(&last), Some(Language::Rust));
+
+    let hidden = format!("{}// vim: ft=rust{}", middle, middle);
+    assert_eq!(from_modeline(&hidden), None);
+}
+
+#[test]
+fn test_detect_precedence() {
+    let checks = [
+        // a known extension beats a modeline
+        ("main.py", "# vim: ft=haskell", Some(Language::Python)),
+        ("main.c", "// regex: ft=python", Some(Language::C)),
+        // otherwise, a modeline beats a shebang
+        (
+            "main",
+            "#!/usr/bin/env python\n# vim: ft=haskell",
+            Some(Language::Haskell),
+        ),
+        // whole file name beats shebang
+        (
+            "SConstruct",
+            "#!/usr/bin/env runghc\n",
+            Some(Language::Python),
+        ),
+        // shebang beats extension
+        (
+            "script.rs",
+            "#!/usr/bin/env python3\n",
+            Some(Language::Python),
+        ),
+        // extension is the last resort
+        ("script.rs", "#!/usr/bin/perl\n", Some(Language::Rust)),
+        ("script", "#!/usr/bin/perl\n", None),
+    ];
+
+    for (path, text, check) in checks.iter() {
+        assert_eq!(
+            &detect(path, text),
+            check,
+            "wrong language for \"{}\"",
+            path
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use crate::diff::{equal, lines, Line, Options};
+use crate::languages::{python, rust};
+
+const EXACT: Options = Options {
+    ignore_whitespace: false,
+};
+const IGNORE_WHITESPACE: Options = Options {
+    ignore_whitespace: true,
+};
+
+#[test]
+fn test_equal() {
+    let pairs = [
+        ("", "", true, true),
+        ("a", "a", true, true),
+        ("a", "b", false, false),
+        ("a // x", "a // y", true, true),
+        ("a /* x */", "a /* y */", true, true),
+        ("a // x\nb", "a\nb", false, true),
+        ("a /* x */ b", "a b", false, true),
+        ("a\n\n\nb", "a\nb", false, true),
+        ("a\n", "a", false, true),
+        ("a /* x */\nb", "a /* y\n */b", false, false),
+        ("/* header */\nfn f() {}", "fn f() {}", false, true),
+        ("a", "a /* unclosed", false, true),
+    ];
+
+    for (a, b, exact, ignoring_whitespace) in pairs.iter() {
+        for (options, check) in [(EXACT, exact), (IGNORE_WHITESPACE, ignoring_whitespace)].iter() {
+            let result = equal(a.chars(), b.chars(), rust(), options).unwrap();
+            assert_eq!(&result, *check, "{:?} and {:?} with {:?}", a, b, options);
+            let result = equal(b.chars(), a.chars(), rust(), options).unwrap();
+            assert_eq!(&result, *check, "{:?} and {:?} with {:?}", b, a, options);
+        }
+    }
+}
+
+#[test]
+fn test_equal_error() {
+    assert!(equal("*/".chars(), "a".chars(), rust(), &EXACT).is_err());
+    assert!(equal("a".chars(), "a */".chars(), rust(), &IGNORE_WHITESPACE).is_err());
+}
+
+#[test]
+fn test_lines() {
+    let line = |number, text: &str| Line {
+        number,
+        text: text.to_string(),
+    };
+
+    let text = "import os  # os\n'''\ndocs\n'''\n\nx  =  1\n";
+    assert_eq!(
+        lines(text.chars(), python(), &EXACT).unwrap(),
+        [
+            line(1, "import os  "),
+            line(4, ""),
+            line(5, ""),
+            line(6, "x  =  1"),
+        ]
+    );
+    assert_eq!(
+        lines(text.chars(), python(), &IGNORE_WHITESPACE).unwrap(),
+        [line(1, "import os"), line(6, "x = 1")]
+    );
+
+    assert_eq!(lines("".chars(), rust(), &EXACT).unwrap(), []);
+    assert_eq!(lines("a".chars(), rust(), &EXACT).unwrap(), [line(1, "a")]);
+    assert_eq!(
+        lines("/*\n*/a\nb".chars(), rust(), &EXACT).unwrap(),
+        [line(2, "a"), line(3, "b")]
+    );
+}
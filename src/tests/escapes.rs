@@ -0,0 +1,66 @@
+use super::strip;
+use crate::validation::{validate, ValidationError};
+use crate::{Anchor, Comment, IntoWithoutComments as _};
+
+#[test]
+fn test_escaped_open_pattern() {
+    let makefile = [Comment::line("#").escape('\\')];
+    assert_eq!(
+        strip("A = a\\#b # c\n\techo \\# # d\n", &makefile),
+        "A = a\\#b \n\techo \\# \n"
+    );
+
+    // gitignore only has comments at the start of a line
+    let gitignore = [Comment::line("#")
+        .escape('\\')
+        .open_anchor(Anchor::LineStart)];
+    assert_eq!(
+        strip("# comment\n\\#file\nname#1\n", &gitignore),
+        "\n\\#file\nname#1\n"
+    );
+}
+
+#[test]
+fn test_escaped_close_pattern() {
+    // an escaped stray close pattern is text rather than an error
+    let language = vec![Comment::block("/*", "*/").escape('\\')];
+    let stripped = "a \\*/ b /* c \\*/ d"
+        .chars()
+        .without_comments(language.into_boxed_slice())
+        .try_collect::<String>();
+    assert_eq!(stripped.unwrap(), "a \\*/ b  d");
+}
+
+#[test]
+fn test_escapes_only_apply_in_text() {
+    // escapes of comments don't apply inside of comments or literals
+    let language = vec![
+        Comment::line("%").escape('\\'),
+        Comment::literal("\"", "\""),
+    ];
+    assert_eq!(strip("a % b \\\nc", &language), "a \nc");
+    assert_eq!(strip("\"\\\" % d", &language), "\"\\\" ");
+}
+
+#[test]
+fn test_unreachable_open_pattern() {
+    let language = [Comment::line("\\%").escape('\\')];
+    let err = validate(&language).unwrap_err();
+    assert_eq!(err, ValidationError::UnreachableOpenPattern { index: 0 });
+    assert_eq!(
+        err.to_string(),
+        "comments[0]: the open pattern can never match"
+    );
+
+    let language = [Comment::line("%").escape('\\'), Comment::line("\\x")];
+    assert_eq!(
+        validate(&language),
+        Err(ValidationError::UnreachableOpenPattern { index: 1 })
+    );
+    // escapes of literals don't apply to regular text
+    let language = [
+        Comment::line("\\x"),
+        Comment::literal("\"", "\"").escape('\\'),
+    ];
+    assert_eq!(validate(&language), Ok(()));
+}
@@ -0,0 +1,132 @@
+use crate::fingerprint::{fingerprint, fingerprint_reader, Fingerprint, Fingerprinter, Options};
+use crate::languages::{c, rust};
+use std::io::{self, Read};
+
+const EXACT: Options = Options {
+    ignore_whitespace: false,
+};
+const IGNORE_WHITESPACE: Options = Options {
+    ignore_whitespace: true,
+};
+
+fn hex(text: &str, options: &Options) -> String {
+    fingerprint(text.chars(), rust(), options)
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_stable_values() {
+    // FNV-1a 128 of the stripped text, these must never change
+    assert_eq!(hex("", &EXACT), "6c62272e07bb014262b821756295c58d");
+    assert_eq!(hex("a // b", &EXACT), hex("a ", &EXACT));
+    assert_eq!(hex("/* x */a", &EXACT), "d228cb696f1a8caf78912b704e4a8964");
+    assert_eq!(
+        hex("fn main() {} // entry\n", &EXACT),
+        hex("fn main() {} \n", &EXACT)
+    );
+    assert_eq!(
+        hex("fn main() {}\n", &EXACT),
+        "7bce4ac9586c3dbe04ae6b58e9ffdc77"
+    );
+    assert_eq!(
+        hex("  a /* x */\n\n\t b   c  \n", &IGNORE_WHITESPACE),
+        "357b98f1af83d94f70814575b221bb59"
+    );
+}
+
+#[test]
+fn test_matches_diff_equal() {
+    use crate::diff::{self, equal};
+
+    let texts = [
+        "",
+        "a",
+        "a // x",
+        "a\n",
+        "a\nb",
+        "a\n\n\nb",
+        "a /* x */ b",
+        "a  b",
+        " a\tb\r\n",
+        "a\rb",
+        "a\u{2028}b\r\n",
+        "a b",
+        "/* header */\nfn f() {}",
+        "fn f() {}",
+    ];
+    for (exact, ignore_whitespace) in [(EXACT, false), (IGNORE_WHITESPACE, true)].iter() {
+        let options = diff::Options {
+            ignore_whitespace: *ignore_whitespace,
+        };
+        for a in texts.iter() {
+            for b in texts.iter() {
+                let same = equal(a.chars(), b.chars(), rust(), &options).unwrap();
+                assert_eq!(
+                    hex(a, exact) == hex(b, exact),
+                    same,
+                    "{:?} and {:?} with {:?}",
+                    a,
+                    b,
+                    exact
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fingerprinter() {
+    let mut fingerprinter = Fingerprinter::new(&EXACT);
+    assert_eq!(
+        fingerprinter.finish(),
+        Fingerprint(0x6c62272e07bb014262b821756295c58d)
+    );
+    fingerprinter.extend("fn main".chars());
+    fingerprinter.extend("() {}\n".chars());
+    assert_eq!(
+        fingerprinter.finish().to_string(),
+        "7bce4ac9586c3dbe04ae6b58e9ffdc77"
+    );
+}
+
+/// Reader returning at most `chunk` bytes at a time, to split multi-byte `char`s.
+struct Chunked<'a> {
+    bytes: &'a [u8],
+    chunk: usize,
+}
+
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.chunk.min(buf.len()).min(self.bytes.len());
+        buf[..n].copy_from_slice(&self.bytes[..n]);
+        self.bytes = &self.bytes[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn test_reader() {
+    let text = "int été = 1; /* ünïcödé 🦀 */ // ∑\nreturn été;\n";
+    let expected = fingerprint(text.chars(), c(), &EXACT).unwrap();
+    for chunk in 1..8 {
+        let reader = Chunked {
+            bytes: text.as_bytes(),
+            chunk,
+        };
+        assert_eq!(fingerprint_reader(reader, c(), &EXACT).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_reader_errors() {
+    let invalid = [&b"a\xffb"[..], b"a\xc3", b"\xe2\x28\xa1", b"\xed\xa0\x80"];
+    for bytes in invalid.iter() {
+        let err = fingerprint_reader(*bytes, c(), &EXACT).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{:?}", bytes);
+    }
+
+    let err = fingerprint_reader(&b"a */"[..], c(), &EXACT).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.get_ref().unwrap().is::<crate::Error>());
+}
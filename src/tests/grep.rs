@@ -0,0 +1,96 @@
+use crate::grep::{grep, scoped_lines, Scope};
+use crate::languages::{python, rust};
+
+fn found(text: &str, scope: Scope, pattern: &str) -> Vec<(String, String)> {
+    grep(text.chars(), rust(), scope, pattern)
+        .unwrap()
+        .into_iter()
+        .map(|m| (m.position.to_string(), m.text))
+        .collect()
+}
+
+#[test]
+fn test_grep_scopes() {
+    let text = "let x = f(); // f(old)\n/* f(a)\n   f(b) */ f(c) /* x */ f(d)\n";
+    let code = found(text, Scope::Code, "f(");
+    assert_eq!(
+        code,
+        [
+            ("1:9".to_string(), "f(".to_string()),
+            ("3:12".to_string(), "f(".to_string()),
+            ("3:25".to_string(), "f(".to_string()),
+        ]
+    );
+
+    let comments = found(text, Scope::Comments, "f(");
+    assert_eq!(
+        comments,
+        [
+            ("1:17".to_string(), "f(".to_string()),
+            ("2:4".to_string(), "f(".to_string()),
+            ("3:4".to_string(), "f(".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_grep_positions() {
+    // matches can span removed comments, they are reported at their first char
+    let text = "ab/* x */cd ééé/**/abcd";
+    let positions = grep(text.chars(), rust(), Scope::Code, "bc")
+        .unwrap()
+        .into_iter()
+        .map(|m| (m.position.column, m.position.offset))
+        .collect::<Vec<_>>();
+    assert_eq!(positions, [(2, 1), (21, 23)]);
+
+    let matches = grep(text.chars(), rust(), Scope::Code, "éé").unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].position.column, 13);
+    assert_eq!(matches[0].line, "abcd éééabcd");
+}
+
+#[test]
+fn test_grep_edge_cases() {
+    assert!(found("abc", Scope::Code, "").is_empty());
+    assert!(found("abc", Scope::Comments, "b").is_empty());
+    assert_eq!(found("a\nb\n", Scope::Code, "a\nb"), []);
+    assert_eq!(found("aaaa", Scope::Code, "aa").len(), 2);
+    assert!(grep("a */".chars(), rust(), Scope::Code, "a").is_err());
+}
+
+#[test]
+fn test_scoped_lines() {
+    let text = "x = 1  # one\n'''\ndoc\n'''\n\ny = 2\n";
+    let code = scoped_lines(text.chars(), python(), Scope::Code).unwrap();
+    let code = code
+        .iter()
+        .map(|line| (line.number, line.text.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(code, [(1, "x = 1  "), (6, "y = 2")]);
+
+    let comments = scoped_lines(text.chars(), python(), Scope::Comments).unwrap();
+    let comments = comments
+        .iter()
+        .map(|line| (line.number, line.text.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(comments, [(1, "# one"), (2, "'''"), (3, "doc"), (4, "'''")]);
+}
+
+#[test]
+fn test_scoped_lines_crlf() {
+    let text = "a = 1 // x\r\nb = 2\r\n\r\n";
+    let code = scoped_lines(text.chars(), rust(), Scope::Code).unwrap();
+    let code = code
+        .iter()
+        .map(|line| (line.number, line.text.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(code, [(1, "a = 1 "), (2, "b = 2")]);
+    let code = scoped_lines(text.chars(), rust(), Scope::Code).unwrap();
+    assert_eq!(code[0].original, "a = 1 // x");
+
+    let comments = scoped_lines(text.chars(), rust(), Scope::Comments).unwrap();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].text, "// x");
+    assert_eq!(comments[0].position(4).to_string(), "1:11");
+}
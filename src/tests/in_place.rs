@@ -0,0 +1,110 @@
+use crate::in_place::{strip_in_place, Options};
+use crate::languages::{c, rust};
+use crate::{Error, Position};
+use std::fs;
+use std::io;
+use tempfile::TempDir;
+
+fn dir_entries(dir: &TempDir) -> Vec<String> {
+    let mut entries = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    entries.sort();
+    entries
+}
+
+#[test]
+fn test_strip_in_place() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("main.rs");
+    fs::write(&path, "fn main() {} // entry\n").unwrap();
+
+    assert!(strip_in_place(&path, rust(), &Options::default()).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "fn main() {} \n");
+    assert_eq!(dir_entries(&dir), ["main.rs"]);
+
+    // nothing left to strip
+    assert!(!strip_in_place(&path, rust(), &Options::default()).unwrap());
+}
+
+#[test]
+fn test_strip_in_place_backup() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("main.c");
+    fs::write(&path, "int x; /* x */\n").unwrap();
+
+    let options = Options {
+        backup_suffix: Some(".orig".to_string()),
+        ..Options::default()
+    };
+    assert!(strip_in_place(&path, c(), &options).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "int x; \n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("main.c.orig")).unwrap(),
+        "int x; /* x */\n"
+    );
+    assert_eq!(dir_entries(&dir), ["main.c", "main.c.orig"]);
+}
+
+#[test]
+fn test_strip_in_place_error() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("main.rs");
+    let text = "// fine\nlet x = 1; */ not fine\n";
+    fs::write(&path, text).unwrap();
+
+    let err = strip_in_place(&path, rust(), &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.get_ref().unwrap().downcast_ref::<Error>(),
+        Some(&Error::UnmatchedClose {
+            close_pat: "*/".into(),
+            open_pat: "/*".into(),
+            position: Position {
+                offset: 19,
+                line: 2,
+                column: 12,
+            },
+        })
+    );
+
+    // the file is untouched and no temporary file is left behind
+    assert_eq!(fs::read_to_string(&path).unwrap(), text);
+    assert_eq!(dir_entries(&dir), ["main.rs"]);
+}
+
+#[test]
+fn test_strip_in_place_preserve_times() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("main.rs");
+    fs::write(&path, "a // b\n").unwrap();
+
+    let mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    let file = fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(mtime).unwrap();
+    drop(file);
+
+    let options = Options {
+        preserve_times: true,
+        ..Options::default()
+    };
+    assert!(strip_in_place(&path, rust(), &options).unwrap());
+    assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), mtime);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_strip_in_place_permissions() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("script.sh");
+    fs::write(&path, "#!/bin/sh\necho 1 # one\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o751)).unwrap();
+
+    assert!(strip_in_place(&path, crate::languages::shell(), &Options::default()).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "#!/bin/sh\necho 1 \n");
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o751);
+}
@@ -0,0 +1,145 @@
+use crate::jsonc::{strip, Options, Reader};
+use crate::languages::json;
+use crate::{Comment, IntoWithoutComments as _};
+use std::io::{self, Read};
+
+const KEEP: Options = Options {
+    remove_trailing_commas: false,
+};
+const REMOVE: Options = Options {
+    remove_trailing_commas: true,
+};
+
+fn without_comments(text: &str) -> String {
+    text.chars().without_comments(json()).collect()
+}
+
+#[test]
+fn test_strings() {
+    let cases = [
+        (r#"{"url": "http://x"}"#, r#"{"url": "http://x"}"#),
+        (r#"{"a": "/* b */"} // c"#, r#"{"a": "/* b */"} "#),
+        (r#""a \" // b" // c"#, r#""a \" // b" "#),
+        (r#""a \\" // b"#, r#""a \\" "#),
+        (r#"'a // b' /* c */"#, r#"'a // b' "#),
+        (r#""it's" // 'x'"#, r#""it's" "#),
+        (r#"/* "a" */ "b""#, r#" "b""#),
+        ("\"unclosed // b", "\"unclosed // b"),
+    ];
+    for (text, expected) in cases.iter() {
+        assert_eq!(&without_comments(text), expected, "{:?}", text);
+    }
+}
+
+#[test]
+fn test_literal_escape() {
+    let language =
+        vec![Comment::line("#"), Comment::literal("<", ">").escape('!')].into_boxed_slice();
+    let stripped = "a <# !> #> # b\n<!!>#"
+        .chars()
+        .without_comments(language)
+        .collect::<String>();
+    assert_eq!(stripped, "a <# !> #> \n<!!>");
+}
+
+#[test]
+fn test_trailing_commas() {
+    let cases = [
+        ("[1, 2,]", "[1, 2,]", "[1, 2]"),
+        ("{\"a\": 1 , \n}", "{\"a\": 1 , \n}", "{\"a\": 1  \n}"),
+        ("[1, // one\n]", "[1, \n]", "[1 \n]"),
+        ("[1, /* one */ ]", "[1,  ]", "[1  ]"),
+        ("[\"a,]\", \",\"]", "[\"a,]\", \",\"]", "[\"a,]\", \",\"]"),
+        ("[[1,],{},]", "[[1,],{},]", "[[1],{}]"),
+        ("[1,,]", "[1,,]", "[1,]"),
+        ("1,", "1,", "1,"),
+        ("1, ", "1, ", "1, "),
+    ];
+    for (text, kept, removed) in cases.iter() {
+        assert_eq!(&strip(text.chars(), &KEEP).unwrap(), kept, "{:?}", text);
+        assert_eq!(
+            &strip(text.chars(), &REMOVE).unwrap(),
+            removed,
+            "{:?}",
+            text
+        );
+    }
+}
+
+/// Reader returning at most `chunk` bytes at a time.
+struct Chunked<'a> {
+    bytes: &'a [u8],
+    chunk: usize,
+}
+
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.chunk.min(buf.len()).min(self.bytes.len());
+        buf[..n].copy_from_slice(&self.bytes[..n]);
+        self.bytes = &self.bytes[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn test_reader_serde() {
+    let config = r#"
+        // settings
+        {
+            "name": "é // not a comment", /* trailing */
+            "urls": [
+                "http://example.com/*", // wildcard
+                "https://example.org",
+            ],
+            "escaped": "\" /* still a string */",
+        }
+    "#;
+
+    let value: serde_json::Value = serde_json::from_reader(Reader::new(
+        Chunked {
+            bytes: config.as_bytes(),
+            chunk: 3,
+        },
+        &REMOVE,
+    ))
+    .unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "name": "é // not a comment",
+            "urls": ["http://example.com/*", "https://example.org"],
+            "escaped": "\" /* still a string */",
+        })
+    );
+
+    assert!(
+        serde_json::from_reader::<_, serde_json::Value>(Reader::new(config.as_bytes(), &KEEP))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_reader_large() {
+    let text = "[\n".to_string() + &"  \"/* x */\", // y\n".repeat(2000) + "]\n";
+    let mut stripped = String::new();
+    Reader::new(text.as_bytes(), &REMOVE)
+        .read_to_string(&mut stripped)
+        .unwrap();
+    assert_eq!(stripped, strip(text.chars(), &REMOVE).unwrap());
+    assert_eq!(stripped.len(), text.len() - 2000 * 4 - 1);
+}
+
+#[test]
+fn test_reader_errors() {
+    let mut out = String::new();
+    let err = Reader::new(&b"{} */"[..], &KEEP)
+        .read_to_string(&mut out)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.get_ref().unwrap().is::<crate::Error>());
+
+    let err = Reader::new(&b"{\"\xff\"}"[..], &KEEP)
+        .read_to_end(&mut Vec::new())
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
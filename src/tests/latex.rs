@@ -0,0 +1,23 @@
+use super::strip;
+use crate::languages::latex;
+
+#[test]
+fn test_line_comments() {
+    let strings = [
+        (
+            "% preamble\n\\documentclass{article}\n",
+            "\n\\documentclass{article}\n",
+        ),
+        ("text % note\nmore", "text \nmore"),
+        ("50\\% off", "50\\% off"),
+        ("50\\% off % really\n", "50\\% off \n"),
+        ("line\\\\% comment", "line\\\\"),
+        ("\\\\\\% kept", "\\\\\\% kept"),
+        ("\\section{A}%\r\n", "\\section{A}\r\n"),
+        ("ends with \\", "ends with \\"),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &latex()), expected, "{:?}", string);
+    }
+}
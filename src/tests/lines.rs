@@ -0,0 +1,84 @@
+use crate::languages::{haskell, python, rust};
+use crate::IntoWithoutComments as _;
+
+type Lines<'a> = &'a [(usize, &'a str)];
+
+fn lines(text: &str, skip_emptied: bool) -> Vec<(usize, String)> {
+    text.chars()
+        .stripped_lines(rust())
+        .skip_emptied(skip_emptied)
+        .map(Result::unwrap)
+        .collect()
+}
+
+#[test]
+fn test_stripped_lines() {
+    let cases: &[(&str, Lines, Lines)] = &[
+        ("", &[], &[]),
+        ("a", &[(1, "a")], &[(1, "a")]),
+        ("a\n", &[(1, "a")], &[(1, "a")]),
+        (
+            "a\n\nb",
+            &[(1, "a"), (2, ""), (3, "b")],
+            &[(1, "a"), (2, ""), (3, "b")],
+        ),
+        ("a // b\nc", &[(1, "a "), (2, "c")], &[(1, "a "), (2, "c")]),
+        ("// a\nb", &[(1, ""), (2, "b")], &[(2, "b")]),
+        ("  // a\n  \n", &[(1, "  "), (2, "  ")], &[(2, "  ")]),
+        ("a /* b\nc\nd */ e", &[(1, "a  e")], &[(1, "a  e")]),
+        ("/* b\nc\nd */\ne", &[(3, ""), (4, "e")], &[(4, "e")]),
+        ("/* b\nc\nd */ e", &[(3, " e")], &[(3, " e")]),
+        ("a\n/* b */", &[(1, "a")], &[(1, "a")]),
+        ("a\n /* b */", &[(1, "a"), (2, " ")], &[(1, "a")]),
+        ("a/* b */\n", &[(1, "a")], &[(1, "a")]),
+    ];
+
+    for (text, all, skipping) in cases.iter() {
+        let to_owned = |lines: Lines| {
+            lines
+                .iter()
+                .map(|&(n, s)| (n, s.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(text, false), to_owned(all), "{:?}", text);
+        assert_eq!(lines(text, true), to_owned(skipping), "{:?}", text);
+    }
+}
+
+#[test]
+fn test_stripped_lines_languages() {
+    let numbers = |text: &str, language| {
+        text.chars()
+            .stripped_lines(language)
+            .skip_emptied(true)
+            .map(|line| line.unwrap().0)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        numbers("x = 1\n'''\ndoc\n'''\ny = 2 # two\n", python()),
+        [1, 5]
+    );
+    assert_eq!(
+        numbers("{- a\n{- b -}\n-}\nmain = f -- c\n", haskell()),
+        [4]
+    );
+}
+
+#[test]
+fn test_stripped_lines_error() {
+    let mut iter = "a\nb */\nc".chars().stripped_lines(rust());
+    assert_eq!(iter.next(), Some(Ok((1, "a".to_string()))));
+    assert!(matches!(iter.next(), Some(Err(_))));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn test_stripped_lines_crlf() {
+    let lines = "a // one\r\n/* two\r\n */\r\nb\r\n"
+        .chars()
+        .stripped_lines(rust())
+        .skip_emptied(true)
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+    assert_eq!(lines, [(1, "a ".to_string()), (4, "b".to_string())]);
+}
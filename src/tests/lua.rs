@@ -0,0 +1,61 @@
+use super::strip;
+use crate::languages::lua;
+
+#[test]
+fn test_line_comments() {
+    let strings = [
+        ("local x = 1 -- one\nreturn x", "local x = 1 \nreturn x"),
+        ("--[ not a block\nx", "\nx"),
+        ("--[= not a block either\nx", "\nx"),
+        ("x = t[1] -- index\n", "x = t[1] \n"),
+        ("s = \"--\" -- dash\n", "s = \"--\" \n"),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &lua()), expected, "{:?}", string);
+    }
+}
+
+#[test]
+fn test_block_comments() {
+    let strings = [
+        ("a --[[ b\nc ]] d", "a  d"),
+        ("a --[==[ b ]] ]=] c ]==] d", "a  d"),
+        ("a --[=[ b\n--]]\n]=]\nc", "a \nc"),
+        ("--[[ unclosed", ""),
+        ("--[[]]x", "x"),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &lua()), expected, "{:?}", string);
+    }
+}
+
+#[test]
+fn test_long_strings() {
+    let strings = [
+        (
+            "s = [[ -- not a comment ]] -- c",
+            "s = [[ -- not a comment ]] ",
+        ),
+        ("s = [==[ ]] --[[ ]==] -- c", "s = [==[ ]] --[[ ]==] "),
+        ("t[ [[k]] ] = 1 -- c", "t[ [[k]] ] = 1 "),
+        ("x = a[b[1]] -- c", "x = a[b[1]] "),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &lua()), expected, "{:?}", string);
+    }
+}
+
+#[test]
+fn test_long_levels() {
+    // any number of `=`s, well past the limit on C++ raw string delimiters
+    for level in [17, 100, 5000].iter() {
+        let eq = "=".repeat(*level);
+        let text = format!("a --[{0}[ b ]{1}] ]{0}] c -- d\n", eq, &eq[1..]);
+        assert_eq!(strip(&text, &lua()), "a  c \n");
+        let text = format!("s = [{0}[ -- ]] ]{0}] -- c", eq);
+        assert_eq!(strip(&text, &lua()), format!("s = [{0}[ -- ]] ]{0}] ", eq));
+    }
+}
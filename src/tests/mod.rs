@@ -1,4 +1,38 @@
+use crate::{Comment, IntoWithoutComments as _};
+
+mod anchors;
+mod annotations;
+mod apl;
+mod batch;
 mod c;
+mod capture;
+mod case;
+mod char_classes;
+mod detect;
+mod diff;
+mod escapes;
+mod fingerprint;
+mod grep;
 mod haskell;
+mod in_place;
+mod jsonc;
+mod latex;
+mod lines;
+mod lua;
+mod patterns;
+mod priority;
 mod python;
+#[cfg(feature = "regex")]
+mod regexes;
+mod registry;
 mod rust;
+mod segments;
+mod shell;
+mod source_map;
+mod stats;
+mod validation;
+
+/// Strips the comments of `language` from `text`.
+fn strip(text: &str, language: &[Comment]) -> String {
+    text.chars().without_comments(language.into()).collect()
+}
@@ -0,0 +1,113 @@
+use super::strip;
+use crate::annotations::annotations;
+use crate::grep::{grep, Scope};
+use crate::languages::{self, Language};
+use crate::stats::LineStats;
+use crate::{Comment, IntoWithoutComments as _};
+
+#[test]
+fn test_line_terminators() {
+    for language in [Language::Rust, Language::C, Language::Json].iter() {
+        for end in ["\n", "\r\n", "\r", "\u{2028}", "\u{2029}"].iter() {
+            let text = format!("a // b{}c // d{}", end, end);
+            let expected = format!("a {}c {}", end, end);
+            assert_eq!(strip(&text, &language.comments()), expected);
+        }
+    }
+
+    assert_eq!(
+        strip("a # b\rc # d\r\n", &languages::python()),
+        "a \rc \r\n"
+    );
+    assert_eq!(
+        strip("a -- b\u{2028}c", &languages::haskell()),
+        "a \u{2028}c"
+    );
+    assert_eq!(strip("a ⍝ b\r\nc", &languages::apl()), "a \r\nc");
+}
+
+#[test]
+fn test_line_terminator_positions() {
+    // every line terminator ends a line the same way, with `\r\n` counted once
+    for end in ["\n", "\r\n", "\r", "\u{2028}", "\u{2029}"].iter() {
+        let text = ["a", "/* TODO: b", "c */ x", "", "y // FIXME d", ""].join(end);
+
+        let stats = LineStats::count(text.chars(), languages::c()).unwrap();
+        assert_eq!(
+            (stats.code, stats.comment, stats.mixed, stats.blank),
+            (1, 1, 2, 1)
+        );
+
+        let lines = text
+            .chars()
+            .stripped_lines(languages::c())
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        let expected = [(1, "a"), (3, " x"), (4, ""), (5, "y ")];
+        let expected = expected.iter().map(|&(n, line)| (n, line.to_string()));
+        assert_eq!(lines, expected.collect::<Vec<_>>());
+
+        let found = grep(text.chars(), languages::c(), Scope::Code, "x").unwrap();
+        assert_eq!(found[0].position.to_string(), "3:6");
+
+        let found = annotations(text.chars(), languages::c(), &["TODO", "FIXME"]).unwrap();
+        let found = found
+            .iter()
+            .map(|annotation| (annotation.position.to_string(), annotation.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(found, [("2:4".to_string(), "b"), ("5:6".to_string(), "d")]);
+    }
+}
+
+#[test]
+fn test_crlf_line_endings() {
+    // the whole line terminator is kept, a CRLF file stays a CRLF file
+    let languages = [
+        (Language::C, "//"),
+        (Language::Rust, "//"),
+        (Language::Python, "#"),
+        (Language::Haskell, "--"),
+    ];
+    for (language, line) in languages.iter() {
+        let text = format!("a {0} x\r\nb\r\n{0} c\r\n\r\nd", line);
+        assert_eq!(strip(&text, &language.comments()), "a \r\nb\r\n\r\n\r\nd");
+    }
+    assert_eq!(strip("a /* x\r\ny */ b\r\n", &languages::c()), "a  b\r\n");
+}
+
+#[test]
+fn test_longest_close_pattern() {
+    // the whole `\r\n` closes the comment rather than `\r` alone
+    let language = vec![Comment::line("//").keep_close_pat(false)].into_boxed_slice();
+    assert_eq!(strip("a // b\r\nc // d\re", &language), "a c e");
+}
+
+#[test]
+fn test_alternative_patterns() {
+    let language = vec![
+        Comment::block("<!--", "-->").alt_close_pat("--!>"),
+        Comment::line("#").alt_open_pat(";").alt_open_pat("REM "),
+    ]
+    .into_boxed_slice();
+    assert_eq!(strip("a <!-- b --> c <!-- d --!> e", &language), "a  c  e");
+    assert_eq!(strip("a # b\nc ; d\nREM e\nf", &language), "a \nc \n\nf");
+
+    // any open pattern can be closed by any close pattern and nests with any other
+    let language = vec![Comment::block("(*", "*)")
+        .alt_open_pat("{")
+        .alt_close_pat("}")
+        .nests(true)]
+    .into_boxed_slice();
+    assert_eq!(strip("a (* b { c *) d } e", &language), "a  e");
+}
+
+#[test]
+fn test_unmatched_alternative_close_pattern() {
+    let language = vec![Comment::block("(*", "*)").alt_close_pat("*>")].into_boxed_slice();
+    let err = "a *> b"
+        .chars()
+        .without_comments(language)
+        .try_collect::<String>()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Got \"*>\" without matching \"(*\" at 1:3");
+}
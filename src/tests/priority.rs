@@ -0,0 +1,47 @@
+use super::strip;
+use crate::Comment;
+
+#[test]
+fn test_longest_match() {
+    let doc = Comment::block("/**", "**/");
+    let block = Comment::block("/*", "*/");
+    for language in [[block.clone(), doc.clone()], [doc.clone(), block.clone()]].iter() {
+        assert_eq!(strip("a /* b */ c", language), "a  c");
+        assert_eq!(strip("a /** b */ c **/ d", language), "a  d");
+        assert_eq!(strip("a /*/ b */ c", language), "a  c");
+    }
+
+    let line = Comment::line("#");
+    let shebang = Comment::line("#!").keep_close_pat(false);
+    for language in [[line.clone(), shebang.clone()], [shebang, line]].iter() {
+        assert_eq!(strip("#!/bin/sh\n# a\nb", language), "\nb");
+    }
+}
+
+#[test]
+fn test_priority() {
+    let language = [
+        Comment::line("//").priority(1),
+        Comment::block("//*", "*//"),
+    ];
+    assert_eq!(strip("a //* b *// c\nd", &language), "a \nd");
+
+    let language = [Comment::line("//"), Comment::block("//*", "*//")];
+    assert_eq!(strip("a //* b *// c\nd", &language), "a  c\nd");
+
+    // with equal priorities and lengths, the first comment declared wins
+    let language = [Comment::line("%"), Comment::block("%", "%")];
+    assert_eq!(strip("a % b % c\nd", &language), "a \nd");
+    let language = [Comment::block("%", "%"), Comment::line("%")];
+    assert_eq!(strip("a % b % c\nd", &language), "a  c\nd");
+}
+
+#[test]
+fn test_open_wins_over_forbidden_close() {
+    // `*/*` could be a stray close pattern but it starts with a longer open pattern
+    let language = [
+        Comment::block("/*", "*/"),
+        Comment::block("*/*", "*/*").allow_close_pat(true),
+    ];
+    assert_eq!(strip("a */* b */* c", &language), "a  c");
+}
@@ -0,0 +1,170 @@
+use super::strip;
+use crate::regex_pattern::MAX_LOOKAHEAD;
+use crate::validation::{validate, ValidationError};
+use crate::{Anchor, CharClass, Comment, Error, IntoWithoutComments as _, RegexPattern};
+
+fn re(re: &str) -> RegexPattern {
+    RegexPattern::new(re).unwrap()
+}
+
+#[test]
+fn test_regex_line_comments() {
+    // an assembler dialect: `;` anywhere, a numbered `.Lnn:` debug label line in the first column
+    let language = [
+        Comment::line(";"),
+        Comment::line("*")
+            .open_regex(re(r"\.L[0-9]+:"))
+            .open_anchor(Anchor::LineStart),
+    ];
+    assert_eq!(
+        strip("* a\n.L12: b\nmov r1, .L3 ; c\n.Lx: d\n", &language),
+        "\n\nmov r1, .L3 \n.Lx: d\n"
+    );
+
+    // case insensitivity comes from the regular expression itself
+    let language = vec![Comment::line("#").open_regex(re(r"(?i)rem(?-u:\b)"))];
+    assert_eq!(strip("a REM b\nRemark # c\n", &language), "a \nRemark \n");
+}
+
+#[test]
+fn test_regex_blocks() {
+    let language = [
+        Comment::regex(re(r"<<([A-Z]+)>>"), re(r"<</[A-Z]+>>")),
+        Comment::literal("\"", "\""),
+    ];
+    assert_eq!(
+        strip("a <<NOTE>> b <<x>> <</NOTE>> c \"<<A>>\"", &language),
+        "a  c \"<<A>>\""
+    );
+
+    // nesting works with regular expressions as well
+    let language = vec![Comment::regex(re(r"\{-+"), re(r"-+\}")).nests(true)];
+    assert_eq!(strip("a {-- b {- c -} d ---} e", &language), "a  e");
+
+    // the longest match wins over a shorter open pattern
+    let language = vec![
+        Comment::line("--"),
+        Comment::regex(re(r"--\[=*\["), re(r"\]=*\]")),
+    ];
+    assert_eq!(strip("a --[==[ b ]==] c -- d", &language), "a  c ");
+}
+
+#[test]
+fn test_regex_char_classes() {
+    let language = vec![Comment::line("#")
+        .open_regex(re(r"//+"))
+        .preceded_by(CharClass::Whitespace)
+        .followed_by(CharClass::Whitespace)];
+    assert_eq!(
+        strip("http://x a /// b\n# c\n", &language),
+        "http://x a \n\n"
+    );
+}
+
+#[test]
+fn test_regex_lookahead() {
+    // the buffer grows to match more than the initial lookahead
+    let language = [Comment::regex(re(r"<!-+"), re(r"-->"))];
+    let dashes = "-".repeat(MAX_LOOKAHEAD / 2);
+    let text = format!("a <!{} b --> c", dashes);
+    assert_eq!(strip(&text, &language), "a  c");
+
+    // even when the regular expression only matches in the end
+    let language = [Comment::line("//").open_regex(re(r"#+!"))];
+    let text = format!("{}! a", "#".repeat(MAX_LOOKAHEAD / 2));
+    assert_eq!(strip(&text, &language), "");
+
+    // but no further than the cap, which includes the `char` following the match
+    let text = format!("{}! a", "#".repeat(MAX_LOOKAHEAD));
+    assert_eq!(strip(&text, &language), "##");
+
+    // where the longest match found so far is kept
+    let language = [Comment::line("//").open_regex(re(r"#+"))];
+    let text = format!("{} a\nb", "#".repeat(2 * MAX_LOOKAHEAD));
+    assert_eq!(strip(&text, &language), "\nb");
+}
+
+#[test]
+fn test_regex_unmatched_close() {
+    let language = vec![Comment::regex(re(r"<<[A-Z]+>>"), re(r"<</[A-Z]+>>"))];
+    let result = "a <</X>> b"
+        .chars()
+        .without_comments(language.into_boxed_slice())
+        .try_collect::<String>();
+    match result {
+        Err(Error::UnmatchedClose {
+            close_pat,
+            open_pat,
+            position,
+        }) => {
+            assert_eq!(close_pat, "<</X>>");
+            assert_eq!(open_pat, "<<[A-Z]+>>");
+            assert_eq!(position.column, 3);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_regex_validation() {
+    assert_eq!(
+        validate(&[Comment::regex(re(r"<<[A-Z]+>>"), re(r"<</[A-Z]+>>"))]),
+        Ok(())
+    );
+
+    // an open regular expression is never considered shadowed
+    let language = [
+        Comment::line("#").priority(1),
+        Comment::line("#").open_regex(re(r"!+")),
+    ];
+    assert_eq!(validate(&language), Ok(()));
+    let language = [Comment::line("#").priority(1), Comment::line("#")];
+    assert_eq!(
+        validate(&language),
+        Err(ValidationError::ShadowedOpenPattern { index: 1, by: 0 })
+    );
+
+    let mut comment = Comment::regex(re("a"), re("b"));
+    comment.open_regexes.clear();
+    assert_eq!(
+        validate(&[comment]),
+        Err(ValidationError::EmptyOpenPattern { index: 0 })
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_regex_deserialize() {
+    let json = r#"{
+        "open_pats": [],
+        "close_pats": [],
+        "open_regex": "<<[A-Z]+>>",
+        "close_regexes": ["<</[A-Z]+>>"]
+    }"#;
+    assert_eq!(
+        serde_json::from_str::<Comment>(json).unwrap(),
+        Comment::regex(re(r"<<[A-Z]+>>"), re(r"<</[A-Z]+>>"))
+    );
+
+    let json = r##"{"open_pat": "#", "close_pat": "\n", "open_regex": "("}"##;
+    let err = serde_json::from_str::<Comment>(json).unwrap_err();
+    assert!(err.to_string().contains("unclosed group"), "{}", err);
+}
+
+#[test]
+fn test_regex_errors() {
+    let err = RegexPattern::new("a(").unwrap_err();
+    assert_eq!(err.regex, "a(");
+    assert!(err.message.contains("unclosed group"), "{}", err);
+    assert!(err
+        .to_string()
+        .starts_with("invalid regular expression \"a(\": "));
+
+    // Unicode word boundaries aren't supported, ASCII ones are
+    assert!(RegexPattern::new(r"\bREM\b").is_err());
+    let language = vec![Comment::line(";").open_regex(re(r"(?-u:\b)REM(?-u:\b)"))];
+    assert_eq!(
+        strip("a REM b\nREMARK c\nxREM d", &language),
+        "a \nREMARK c\nxREM d"
+    );
+}
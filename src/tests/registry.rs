@@ -0,0 +1,196 @@
+use crate::languages::{c, haskell, python, rust, Language, UnknownLanguage};
+use crate::registry::{Definition, Registry};
+use crate::{Comment, IntoWithoutComments as _};
+
+#[test]
+fn test_language_from_str() {
+    let names = [
+        ("rust", Language::Rust),
+        ("Rust", Language::Rust),
+        ("rs", Language::Rust),
+        ("c", Language::C),
+        ("C", Language::C),
+        ("c++", Language::C),
+        ("cpp", Language::C),
+        ("CC", Language::C),
+        ("java", Language::C),
+        ("python", Language::Python),
+        ("python3", Language::Python),
+        ("py", Language::Python),
+        ("haskell", Language::Haskell),
+        ("hs", Language::Haskell),
+    ];
+
+    for (name, check) in names.iter() {
+        assert_eq!(&name.parse::<Language>().unwrap(), check);
+    }
+
+    for name in ["", "cobol", "rust ", "c+"].iter() {
+        assert_eq!(
+            name.parse::<Language>(),
+            Err(UnknownLanguage(name.to_string()))
+        );
+    }
+}
+
+#[test]
+fn test_language_names() {
+    for language in Language::iter() {
+        assert_eq!(language.name().parse::<Language>().unwrap(), language);
+        assert_eq!(language.to_string().parse::<Language>().unwrap(), language);
+        for alias in language.aliases() {
+            assert_eq!(alias.parse::<Language>().unwrap(), language);
+        }
+    }
+
+    let display_names = Language::iter().map(|l| l.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        display_names,
+        ["Rust", "C", "Python", "Haskell", "JSON", "APL", "Shell", "LaTeX", "Batch", "Lua"]
+    );
+}
+
+#[test]
+fn test_language_comments() {
+    assert_eq!(Language::Rust.comments(), rust());
+    assert_eq!(Language::C.comments(), c());
+    assert_eq!(Language::Python.comments(), python());
+    assert_eq!(Language::Haskell.comments(), haskell());
+}
+
+#[test]
+fn test_registry_builtins() {
+    let registry = Registry::new();
+
+    assert_eq!(registry.iter().count(), Language::ALL.len());
+    for language in Language::iter() {
+        let definition = registry.get(language.name()).unwrap();
+        assert_eq!(definition, &Definition::from(language));
+    }
+
+    assert_eq!(registry.get("c++").unwrap().name, "C");
+    assert_eq!(registry.get("PYTHON").unwrap().name, "Python");
+    assert!(registry.get("lisp").is_none());
+    assert_eq!(
+        registry.comments("lisp"),
+        Err(UnknownLanguage("lisp".to_string()))
+    );
+    assert!(Registry::empty().get("rust").is_none());
+}
+
+#[test]
+fn test_registry_register() {
+    let lisp = vec![Comment::line(";"), Comment::block("#|", "|#").nests(true)].into_boxed_slice();
+
+    let mut registry = Registry::new();
+    registry.register(Definition::new("Lisp", lisp.clone()).alias("scheme"));
+
+    assert_eq!(registry.get("lisp").unwrap().comments, lisp);
+    assert_eq!(registry.comments("Scheme").unwrap(), lisp);
+    assert_eq!(registry.iter().count(), Language::ALL.len() + 1);
+
+    let without_comments = "(a) ; b\n#| c #| d |# |#(e)"
+        .chars()
+        .without_comments(registry.comments("lisp").unwrap())
+        .collect::<String>();
+    assert_eq!(&without_comments, "(a) \n(e)");
+}
+
+#[test]
+fn test_registry_precedence() {
+    let mut registry = Registry::new();
+
+    // an alias shadows a built-in alias
+    registry.register(
+        Definition::new("C++", vec![Comment::line("//")].into_boxed_slice()).alias("cpp"),
+    );
+    assert_eq!(registry.get("cpp").unwrap().name, "C++");
+    assert_eq!(registry.get("c").unwrap().name, "C");
+
+    // a definition with the same name replaces the old one
+    registry.register(Definition::new(
+        "rust",
+        vec![Comment::line("//")].into_boxed_slice(),
+    ));
+    assert_eq!(registry.get("RUST").unwrap().name, "rust");
+    assert!(registry.get("rs").is_none());
+    assert_eq!(registry.iter().count(), Language::ALL.len() + 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_definition_deserialize() {
+    let json = r#"{
+        "name": "Lisp",
+        "extensions": ["lisp"],
+        "comments": [
+            {
+                "open_pat": ";",
+                "close_pats": ["\r\n", "\n", "\r", "\u2028", "\u2029"],
+                "keep_close_pat": true,
+                "allow_close_pat": true
+            },
+            { "open_pat": "\"", "close_pat": "\"", "literal": true, "escape": "\\" }
+        ]
+    }"#;
+    let definition = serde_json::from_str::<Definition>(json).unwrap();
+    assert_eq!(
+        definition,
+        Definition::new(
+            "Lisp",
+            vec![
+                Comment::line(";"),
+                Comment::literal("\"", "\"").escape('\\')
+            ]
+            .into_boxed_slice(),
+        )
+        .extension("lisp")
+    );
+
+    let round_trip = serde_json::to_string(&Definition::from(Language::Rust)).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Definition>(&round_trip).unwrap(),
+        Definition::from(Language::Rust)
+    );
+
+    assert!(serde_json::from_str::<Definition>(r#"{"name": "x", "comments": [{}]}"#).is_err());
+
+    // misspelled optional keys are errors rather than silently ignored
+    let misspelled = [
+        r#"{"name": "x", "extension": ["x"], "comments": []}"#,
+        r#"{"name": "x", "comments": [{"open_pat": "(*", "close_pat": "*)", "nest": true}]}"#,
+        r#"{"name": "x", "comments": [{"open_pat": "[", "close_pat": "]",
+            "capture": {"chars": {"one_of": "="}, "max_length": 4}}]}"#,
+    ];
+    for json in misspelled.iter() {
+        assert!(
+            serde_json::from_str::<Definition>(json).is_err(),
+            "{} was accepted",
+            json
+        );
+    }
+}
+
+#[test]
+fn test_registry_detect() {
+    let mut registry = Registry::new();
+    assert_eq!(registry.detect("a.py", "").unwrap().name, "Python");
+    assert!(registry.detect("a.unknown", "").is_none());
+
+    registry.register(
+        Definition::new("Custom Python", rust())
+            .alias("python")
+            .extension("PY"),
+    );
+    assert_eq!(registry.detect("a.py", "").unwrap().name, "Custom Python");
+    // detected by shebang, then looked up by name
+    assert_eq!(
+        registry
+            .detect("script", "#!/usr/bin/python\n")
+            .unwrap()
+            .name,
+        "Custom Python"
+    );
+
+    assert!(Registry::empty().detect("main.rs", "").is_none());
+}
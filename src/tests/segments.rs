@@ -0,0 +1,129 @@
+use crate::languages::{python, rust};
+use crate::{Error, IntoWithoutComments as _, Position, Segment, SegmentKind};
+
+fn kinds_and_texts(string: &str) -> Vec<(SegmentKind, String)> {
+    string
+        .chars()
+        .segments(rust())
+        .map(|segment| {
+            let segment = segment.unwrap();
+            (segment.kind, segment.text)
+        })
+        .collect()
+}
+
+#[test]
+fn test_segments() {
+    use SegmentKind::{Comment as C, Text as T};
+
+    let strings: &[(&str, &[(SegmentKind, &str)])] = &[
+        ("", &[]),
+        ("text", &[(T, "text")]),
+        ("// comment", &[(C, "// comment")]),
+        ("a // b\nc", &[(T, "a "), (C, "// b"), (T, "\nc")]),
+        ("a /* b */ c", &[(T, "a "), (C, "/* b */"), (T, " c")]),
+        ("/* a *//* b */", &[(C, "/* a */"), (C, "/* b */")]),
+        ("/* a /* b */ c */d", &[(C, "/* a /* b */ c */"), (T, "d")]),
+        ("//\n//\n", &[(C, "//"), (T, "\n"), (C, "//"), (T, "\n")]),
+        ("a/* unclosed", &[(T, "a"), (C, "/* unclosed")]),
+    ];
+
+    for (string, check) in strings.iter() {
+        let check = check
+            .iter()
+            .map(|&(kind, text)| (kind, text.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds_and_texts(string),
+            check,
+            "wrong segments for {:?}",
+            string
+        );
+    }
+}
+
+#[test]
+fn test_segments_text_matches_without_comments() {
+    let strings = [
+        "a // b\nc /* d /* e */ */ f",
+        "/**/x/***/y//z",
+        "Line// comment\nAnd block/* comment */",
+    ];
+
+    for string in strings.iter() {
+        let text = string
+            .chars()
+            .segments(rust())
+            .map(Result::unwrap)
+            .filter(|segment| !segment.is_comment())
+            .map(|segment| segment.text)
+            .collect::<String>();
+        let without_comments = string.chars().without_comments(rust()).collect::<String>();
+        assert_eq!(text, without_comments);
+    }
+}
+
+#[test]
+fn test_segment_positions() {
+    let segments = "x = 1 # one\n'''\nmulti\n''' y"
+        .chars()
+        .segments(python())
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+
+    let position = |offset, line, column| Position {
+        offset,
+        line,
+        column,
+    };
+    assert_eq!(
+        segments,
+        [
+            Segment {
+                kind: SegmentKind::Text,
+                text: "x = 1 ".to_string(),
+                start: position(0, 1, 1),
+                end: position(6, 1, 7),
+            },
+            Segment {
+                kind: SegmentKind::Comment,
+                text: "# one".to_string(),
+                start: position(6, 1, 7),
+                end: position(11, 1, 12),
+            },
+            Segment {
+                kind: SegmentKind::Text,
+                text: "\n".to_string(),
+                start: position(11, 1, 12),
+                end: position(12, 2, 1),
+            },
+            Segment {
+                kind: SegmentKind::Comment,
+                text: "'''\nmulti\n'''".to_string(),
+                start: position(12, 2, 1),
+                end: position(25, 4, 4),
+            },
+            Segment {
+                kind: SegmentKind::Text,
+                text: " y".to_string(),
+                start: position(25, 4, 4),
+                end: position(27, 4, 6),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_segments_error() {
+    let mut segments = "a // b\n*/ c".chars().segments(rust());
+
+    assert_eq!(segments.next().unwrap().unwrap().text, "a ");
+    assert_eq!(segments.next().unwrap().unwrap().text, "// b");
+    match segments.next() {
+        Some(Err(Error::UnmatchedClose { position, .. })) => {
+            assert_eq!((position.line, position.column), (2, 1))
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(segments.next(), None);
+}
@@ -0,0 +1,37 @@
+use super::strip;
+use crate::languages::shell;
+
+#[test]
+fn test_line_comments() {
+    let strings = [
+        ("#!/bin/sh\necho hi # greet\n", "#!/bin/sh\necho hi \n"),
+        ("#!/bin/sh\r\n#!x\r\n", "#!/bin/sh\r\n\r\n"),
+        (" #!/bin/sh\n", " \n"),
+        ("  # indented\n", "  \n"),
+        ("echo $#\n", "echo $#\n"),
+        ("echo a#b\n", "echo a#b\n"),
+        ("echo ${x#prefix} # strip\n", "echo ${x#prefix} \n"),
+        ("echo\t# tab\r\nls", "echo\t\r\nls"),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &shell()), expected, "{:?}", string);
+    }
+}
+
+#[test]
+fn test_quotes() {
+    let strings = [
+        ("echo '# not a comment' # one", "echo '# not a comment' "),
+        ("echo \" # \\\" # \" # two", "echo \" # \\\" # \" "),
+        ("echo 'a\\' # three", "echo 'a\\' "),
+        // escaped quotes outside of quotes don't start a literal
+        ("echo it\\'s # four\n", "echo it\\'s \n"),
+        ("echo \\\" # five\n", "echo \\\" \n"),
+        ("echo \\# six\n", "echo \\# six\n"),
+    ];
+
+    for (string, expected) in strings.iter() {
+        assert_eq!(&strip(string, &shell()), expected, "{:?}", string);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::languages::{c, python, rust};
+use crate::source_map::{strip, Location};
+use crate::{Comment, IntoWithoutComments as _};
+
+fn line_of(text: &str, offset: usize) -> usize {
+    1 + text[..offset].matches('\n').count()
+}
+
+/// Checks that every offset of the stripped text maps to the same `char` in the original text
+/// and back.
+fn check(text: &str, language: Box<[Comment]>) {
+    let (stripped, map) = strip(text.chars(), language.clone()).unwrap();
+    assert_eq!(
+        stripped,
+        text.chars().without_comments(language).collect::<String>()
+    );
+
+    for (offset, c) in stripped.char_indices() {
+        let original = map.original(offset).unwrap();
+        assert_eq!(
+            text[original.offset..].chars().next(),
+            Some(c),
+            "{:?}",
+            text
+        );
+        assert_eq!(original.line, line_of(text, original.offset));
+        assert_eq!(
+            map.stripped(original.offset),
+            Some(Location {
+                offset,
+                line: line_of(&stripped, offset),
+            })
+        );
+    }
+
+    assert_eq!(
+        map.original(stripped.len()),
+        Some(Location {
+            offset: text.len(),
+            line: line_of(text, text.len()),
+        })
+    );
+    assert_eq!(map.original(stripped.len() + 1), None);
+    assert_eq!(map.stripped(text.len() + 1), None);
+
+    let kept = text
+        .char_indices()
+        .filter(|&(offset, _)| map.stripped(offset).is_some())
+        .count();
+    assert_eq!(kept, stripped.chars().count());
+}
+
+#[test]
+fn test_round_trip() {
+    let texts = [
+        "",
+        "text",
+        "// only a comment",
+        "a // b\nc",
+        "a /* b */ c",
+        "/* a *//* b */d",
+        "fn é() {} /* ünïcödé 🦀 */ // ∑\nlet x = 1;\n",
+        "a/* unclosed\n\n",
+    ];
+    for text in texts.iter() {
+        check(text, rust());
+        check(text, c());
+    }
+    check("/* a /* b */ c */d\n\ne", rust());
+    check("x = 1  # one\n'''\ndoc\n'''\ny = 2", python());
+}
+
+#[test]
+fn test_removed() {
+    let text = "a /* b\nc */ d // e\nf";
+    let (stripped, map) = strip(text.chars(), rust()).unwrap();
+    assert_eq!(stripped, "a  d \nf");
+
+    for offset in 2..11 {
+        assert_eq!(map.stripped(offset), None, "{}", offset);
+    }
+    assert_eq!(map.stripped(11), Some(Location { offset: 2, line: 1 }));
+    assert_eq!(
+        map.original(3),
+        Some(Location {
+            offset: 12,
+            line: 2
+        })
+    );
+    assert_eq!(
+        map.original(6),
+        Some(Location {
+            offset: 19,
+            line: 3
+        })
+    );
+}
+
+#[test]
+fn test_compact() {
+    let text = "fn main() {\n    let x = 1; // one\n    /* two */ f(x);\n}\n";
+    let (_, map) = strip(text.chars(), rust()).unwrap();
+    // one entry per line, plus one after each comment that doesn't end its line
+    assert_eq!(map.len(), 6);
+}
+
+#[test]
+fn test_error() {
+    assert!(strip("a */".chars(), rust()).is_err());
+}
@@ -0,0 +1,68 @@
+use crate::languages::{haskell, python, rust};
+use crate::stats::LineStats;
+
+fn stats(code: usize, comment: usize, mixed: usize, blank: usize) -> LineStats {
+    LineStats {
+        code,
+        comment,
+        mixed,
+        blank,
+    }
+}
+
+#[test]
+fn test_line_stats() {
+    let strings = [
+        ("", stats(0, 0, 0, 0)),
+        ("\n", stats(0, 0, 0, 1)),
+        ("a", stats(1, 0, 0, 0)),
+        ("a\n", stats(1, 0, 0, 0)),
+        ("a\nb", stats(2, 0, 0, 0)),
+        ("  \n\t\n", stats(0, 0, 0, 2)),
+        ("// a", stats(0, 1, 0, 0)),
+        ("    // a\n", stats(0, 1, 0, 0)),
+        ("a // b\n", stats(0, 0, 1, 0)),
+        ("/* a */ b", stats(0, 0, 1, 0)),
+        ("/*\n\n*/\n", stats(0, 2, 0, 1)),
+        ("/* a\n b */ c\n d\n", stats(1, 1, 1, 0)),
+        ("a /* b /* c\n*/ */\n", stats(0, 1, 1, 0)),
+        ("//\n//\n", stats(0, 2, 0, 0)),
+    ];
+
+    for (string, check) in strings.iter() {
+        let stats = LineStats::count(string.chars(), rust()).unwrap();
+        assert_eq!(&stats, check, "wrong stats for {:?}", string);
+    }
+}
+
+#[test]
+fn test_line_stats_languages() {
+    let text = "x = 1\n# comment\n'''\ndoc\n'''\n\ny = 2 # two\n";
+    assert_eq!(
+        LineStats::count(text.chars(), python()).unwrap(),
+        stats(1, 4, 1, 1)
+    );
+
+    let text = "{- module\n   doc -}\nmain = pure () -- entry\n";
+    assert_eq!(
+        LineStats::count(text.chars(), haskell()).unwrap(),
+        stats(0, 2, 1, 0)
+    );
+}
+
+#[test]
+fn test_line_stats_sum() {
+    let all = vec![stats(1, 2, 3, 4), stats(10, 20, 30, 40)];
+    let sum = all.into_iter().sum::<LineStats>();
+    assert_eq!(sum, stats(11, 22, 33, 44));
+    assert_eq!(sum.total(), 110);
+
+    let mut acc = LineStats::default();
+    acc += stats(1, 1, 1, 1);
+    assert_eq!(acc, stats(1, 1, 1, 1));
+}
+
+#[test]
+fn test_line_stats_error() {
+    assert!(LineStats::count("a */".chars(), rust()).is_err());
+}
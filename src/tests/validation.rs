@@ -0,0 +1,147 @@
+use crate::languages::Language;
+use crate::validation::{validate, ValidationError};
+use crate::{Anchor, Comment};
+
+#[test]
+fn test_builtin_languages_are_valid() {
+    for language in Language::iter() {
+        assert_eq!(validate(&language.comments()), Ok(()), "{}", language);
+    }
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!(validate(&[]), Err(ValidationError::NoComments));
+    assert_eq!(
+        validate(&[Comment::line("")]),
+        Err(ValidationError::EmptyOpenPattern { index: 0 })
+    );
+    let err = validate(&[Comment::line("#"), Comment::block("<", "")]).unwrap_err();
+    assert_eq!(err, ValidationError::EmptyClosePattern { index: 1 });
+    assert_eq!(err.to_string(), "comments[1]: the close pattern is empty");
+}
+
+#[test]
+fn test_shadowed_open_patterns() {
+    // the longest pattern wins whatever the order
+    assert_eq!(
+        validate(&[Comment::line("///"), Comment::line("//")]),
+        Ok(())
+    );
+    assert_eq!(
+        validate(&[Comment::line("//"), Comment::line("///")]),
+        Ok(())
+    );
+    assert_eq!(
+        validate(&[Comment::line("//").priority(1), Comment::line("///")]),
+        Err(ValidationError::ShadowedOpenPattern { index: 1, by: 0 })
+    );
+    assert_eq!(
+        validate(&[Comment::line("///"), Comment::line("//").priority(1)]),
+        Err(ValidationError::ShadowedOpenPattern { index: 0, by: 1 })
+    );
+    assert_eq!(
+        validate(&[Comment::line("//"), Comment::line("///").priority(-1)]),
+        Err(ValidationError::ShadowedOpenPattern { index: 1, by: 0 })
+    );
+    assert_eq!(
+        validate(&[
+            Comment::line("#"),
+            Comment::block("/*", "*/"),
+            Comment::literal("/*", "x"),
+        ]),
+        Err(ValidationError::ShadowedOpenPattern { index: 2, by: 1 })
+    );
+    assert_eq!(
+        validate(&[
+            Comment::block("/*", "*/"),
+            Comment::literal("/*", "x").priority(1),
+        ]),
+        Err(ValidationError::ShadowedOpenPattern { index: 0, by: 1 })
+    );
+}
+
+#[test]
+fn test_alternative_patterns() {
+    assert_eq!(
+        validate(&[Comment::line("#").alt_open_pat("")]),
+        Err(ValidationError::EmptyOpenPattern { index: 0 })
+    );
+    assert_eq!(
+        validate(&[Comment::block("(*", "*)").alt_close_pat("")]),
+        Err(ValidationError::EmptyClosePattern { index: 0 })
+    );
+    // a comment is only shadowed if all of its open patterns are
+    let language = [Comment::line("#"), Comment::line("#").alt_open_pat(";")];
+    assert_eq!(validate(&language), Ok(()));
+    let language = [
+        Comment::line("#"),
+        Comment::line(";"),
+        Comment::line("#").alt_open_pat(";"),
+    ];
+    assert_eq!(
+        validate(&language),
+        Err(ValidationError::ShadowedOpenPattern { index: 2, by: 0 })
+    );
+    assert_eq!(
+        validate(&[Comment::literal("'", "'").alt_close_pat("\\'").escape('\\')]),
+        Err(ValidationError::EscapedClosePattern { index: 0 })
+    );
+    assert_eq!(
+        validate(&[Comment::block("{", "}").alt_close_pat("{").nests(true)]),
+        Err(ValidationError::AmbiguousNesting { index: 0 })
+    );
+}
+
+#[test]
+fn test_escaped_close_patterns() {
+    let err = validate(&[Comment::literal("\"", "\\\"").escape('\\')]).unwrap_err();
+    assert_eq!(err, ValidationError::EscapedClosePattern { index: 0 });
+    assert_eq!(
+        err.to_string(),
+        "comments[0]: the close pattern starts with the escape character"
+    );
+    assert_eq!(validate(&[Comment::literal("\"", "\\\"")]), Ok(()));
+    // escapes only apply to literals
+    assert_eq!(validate(&[Comment::block("<", "\\>").escape('\\')]), Ok(()));
+}
+
+#[test]
+fn test_unreachable_close_patterns() {
+    let err = validate(&[Comment::block("<", ">").close_anchor(Anchor::FileStart)]).unwrap_err();
+    assert_eq!(err, ValidationError::UnreachableClosePattern { index: 0 });
+    assert_eq!(
+        err.to_string(),
+        "comments[0]: the close pattern can only match at the start of the input"
+    );
+    assert_eq!(
+        validate(&[Comment::block("<", ">").open_anchor(Anchor::FileStart)]),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_ambiguous_nesting() {
+    let err = validate(&[Comment::block("%%", "%%").nests(true)]).unwrap_err();
+    assert_eq!(err, ValidationError::AmbiguousNesting { index: 0 });
+    assert_eq!(
+        err.to_string(),
+        "comments[0]: a nesting comment needs different open and close patterns"
+    );
+    assert_eq!(validate(&[Comment::block("%%", "%%")]), Ok(()));
+}
+
+#[test]
+fn test_try_without_comments() {
+    use crate::IntoWithoutComments as _;
+
+    let language = vec![Comment::block("(*", "*)").nests(true)].into_boxed_slice();
+    let stripped = "a (* (* b *) *) c"
+        .chars()
+        .try_without_comments(language)
+        .unwrap()
+        .collect::<String>();
+    assert_eq!(stripped, "a  c");
+
+    assert!("a".chars().try_without_comments(Box::new([])).is_err());
+}
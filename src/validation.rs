@@ -0,0 +1,232 @@
+use crate::without_comments::{eq_ignore_case, str_eq_ignore_case, Anchor, Comment};
+
+/// A problem with a list of comment specifications that would make `without_comments`
+/// misbehave. Indices refer to the position of the comment in the list, starting at 0.
+#[derive(Clone, Debug, PartialEq, Eq, Display)]
+pub enum ValidationError {
+    /// The list has no comments at all
+    #[display(fmt = "no comments are defined")]
+    NoComments,
+    /// An open pattern is empty, or there is none (nor any open regular expression)
+    #[display(fmt = "comments[{}]: the open pattern is empty", index)]
+    EmptyOpenPattern { index: usize },
+    /// A close pattern is empty, or there is none (nor any close regular expression)
+    #[display(fmt = "comments[{}]: the close pattern is empty", index)]
+    EmptyClosePattern { index: usize },
+    /// Every open pattern starts with an open pattern of another comment which always wins
+    /// over it, either because it has a higher priority or because they are the same and the
+    /// other comment is declared first, so the comment can never open
+    #[display(
+        fmt = "comments[{}]: the open pattern is shadowed by that of comments[{}]",
+        index,
+        by
+    )]
+    ShadowedOpenPattern { index: usize, by: usize },
+    /// An open pattern can never match, either because it starts with the escape character of a
+    /// comment, which is handled first in regular text, or because its capture can never match
+    #[display(fmt = "comments[{}]: the open pattern can never match", index)]
+    UnreachableOpenPattern { index: usize },
+    /// A close pattern of a literal starts with its escape character, which is handled first
+    /// inside of the literal, so the close pattern can never match
+    #[display(
+        fmt = "comments[{}]: the close pattern starts with the escape character",
+        index
+    )]
+    EscapedClosePattern { index: usize },
+    /// The close patterns are anchored to the start of the input (`Anchor::FileStart`), where
+    /// the comment can't be open yet, so they can never match
+    #[display(
+        fmt = "comments[{}]: the close pattern can only match at the start of the input",
+        index
+    )]
+    UnreachableClosePattern { index: usize },
+    /// A nesting comment has an open pattern that is also one of its close patterns, so it's
+    /// impossible to tell whether a pattern opens a nested comment or closes the current one
+    #[display(
+        fmt = "comments[{}]: a nesting comment needs different open and close patterns",
+        index
+    )]
+    AmbiguousNesting { index: usize },
+    /// A comment with a capture nests, which isn't supported
+    #[display(fmt = "comments[{}]: a comment with a capture can't nest", index)]
+    NestingCapture { index: usize },
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks that a list of comment specifications, such as one loaded from a configuration file,
+/// can be used with `without_comments` and that every pattern in it can match. Returns the first
+/// problem found.
+///
+/// Some patterns that can never match are not detected:
+/// - an open pattern that is only shadowed by several other comments together, or by a comment
+///   with a capture or a regular expression
+/// - a regular expression that is shadowed or can never match, as regular expressions are only
+///   checked for being present
+/// - a capture whose `chars` can never make up `min_len` `char`s, such as an empty `one_of`
+///
+/// # Example
+///
+/// ```
+/// use no_comment::validation::{validate, ValidationError};
+/// use no_comment::{languages, Comment};
+///
+/// assert_eq!(validate(&languages::rust()), Ok(()));
+/// assert_eq!(
+///     validate(&[Comment::line("#"), Comment::block("", "*/")]),
+///     Err(ValidationError::EmptyOpenPattern { index: 1 })
+/// );
+/// ```
+pub fn validate(language: &[Comment]) -> Result<(), ValidationError> {
+    if language.is_empty() {
+        return Err(ValidationError::NoComments);
+    }
+
+    for (index, comment) in language.iter().enumerate() {
+        // regular expressions can stand in for the patterns
+        if comment.open_pats.is_empty() && !has_open_regexes(comment)
+            || comment.open_pats.iter().any(|pat| pat.is_empty())
+        {
+            return Err(ValidationError::EmptyOpenPattern { index });
+        }
+        // with a capture, the close pattern is only a prefix of the actual one
+        let close_can_be_empty = comment
+            .capture
+            .as_ref()
+            .is_some_and(|capture| capture.min_len > 0 || !capture.close_suffix.is_empty());
+        if comment.close_pats.is_empty() && !has_close_regexes(comment)
+            || !close_can_be_empty && comment.close_pats.iter().any(|pat| pat.is_empty())
+        {
+            return Err(ValidationError::EmptyClosePattern { index });
+        }
+    }
+
+    // in regular text, the escape characters of comments are handled before open patterns
+    let text_escapes = language
+        .iter()
+        .filter(|comment| !comment.literal)
+        .filter_map(|comment| comment.escape)
+        .collect::<Vec<_>>();
+
+    for (index, comment) in language.iter().enumerate() {
+        if comment
+            .open_pats
+            .iter()
+            .any(|pat| pat.starts_with(&text_escapes[..]))
+        {
+            return Err(ValidationError::UnreachableOpenPattern { index });
+        }
+        if let Some(capture) = &comment.capture {
+            if capture.min_len > capture.max_len {
+                return Err(ValidationError::UnreachableOpenPattern { index });
+            }
+            if comment.nests {
+                return Err(ValidationError::NestingCapture { index });
+            }
+        }
+
+        // the open pattern with the highest priority wins, then the longest one, then the
+        // first one declared
+        let shadowed_by = |open_pat: &str| {
+            language.iter().enumerate().position(|(by, other)| {
+                by != index
+                    && other.open_anchor.covers(comment.open_anchor)
+                    && other.preceded_by.covers(comment.preceded_by)
+                    && (other.ignore_case || !comment.ignore_case)
+                    // an open pattern with a capture doesn't always match
+                    && other.capture.is_none()
+                    && other.open_pats.iter().any(|other_pat| {
+                        let rest = match strip_prefix(open_pat, other_pat, other.ignore_case) {
+                            Some(rest) => rest,
+                            None => return false,
+                        };
+                        // the other pattern must also accept what follows it
+                        let followed = match rest.chars().next() {
+                            Some(next) => other.followed_by.matches(Some(next)),
+                            None => other.followed_by.covers(comment.followed_by),
+                        };
+                        followed
+                            && (other.priority > comment.priority
+                                || other.priority == comment.priority
+                                    && rest.is_empty()
+                                    && comment.capture.is_none()
+                                    && by < index)
+                    })
+            })
+        };
+        let mut shadowed = comment.open_pats.iter().map(|pat| shadowed_by(pat));
+        if let Some(Some(by)) = shadowed.next() {
+            // regular expressions are never considered shadowed
+            if shadowed.all(|other| other.is_some()) && !has_open_regexes(comment) {
+                return Err(ValidationError::ShadowedOpenPattern { index, by });
+            }
+        }
+
+        if comment.close_anchor == Anchor::FileStart {
+            return Err(ValidationError::UnreachableClosePattern { index });
+        }
+
+        // inside of a literal, the escape character is handled before the close pattern
+        if comment.literal {
+            if let Some(escape) = comment.escape {
+                if comment.close_pats.iter().any(|pat| pat.starts_with(escape)) {
+                    return Err(ValidationError::EscapedClosePattern { index });
+                }
+            }
+        }
+
+        let same = |a: &str, b: &str| match comment.ignore_case {
+            true => str_eq_ignore_case(a, b),
+            false => a == b,
+        };
+        if comment.nests
+            && !comment.literal
+            && comment.open_pats.iter().any(|open_pat| {
+                comment
+                    .close_pats
+                    .iter()
+                    .any(|close_pat| same(open_pat, close_pat))
+            })
+        {
+            return Err(ValidationError::AmbiguousNesting { index });
+        }
+    }
+    Ok(())
+}
+
+/// Whether a comment has open regular expressions, as alternatives to its open patterns.
+#[cfg(feature = "regex")]
+fn has_open_regexes(comment: &Comment) -> bool {
+    !comment.open_regexes.is_empty()
+}
+
+#[cfg(not(feature = "regex"))]
+fn has_open_regexes(_: &Comment) -> bool {
+    false
+}
+
+/// Whether a comment has close regular expressions, as alternatives to its close patterns.
+#[cfg(feature = "regex")]
+fn has_close_regexes(comment: &Comment) -> bool {
+    !comment.close_regexes.is_empty()
+}
+
+#[cfg(not(feature = "regex"))]
+fn has_close_regexes(_: &Comment) -> bool {
+    false
+}
+
+/// Strips `prefix` from the start of `pat`, optionally ignoring case.
+fn strip_prefix<'a>(pat: &'a str, prefix: &str, ignore_case: bool) -> Option<&'a str> {
+    if !ignore_case {
+        return pat.strip_prefix(prefix);
+    }
+    let mut chars = pat.char_indices();
+    for p in prefix.chars() {
+        match chars.next() {
+            Some((_, c)) if eq_ignore_case(c, p) => (),
+            _ => return None,
+        }
+    }
+    Some(chars.as_str())
+}
@@ -0,0 +1,42 @@
+use crate::position::is_line_terminator;
+
+/// Streaming whitespace normalization: runs of whitespace are replaced by a single space,
+/// leading and trailing whitespace is removed from each line, blank lines are removed, and
+/// there is no trailing newline. Every line terminator ends a line, as with `StrippedLines`,
+/// since blank lines are removed, a `\r\n` ends a single one.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Normalizer {
+    /// Whether a space should be output before the next non-whitespace `char`
+    pending_space: bool,
+    /// Whether a newline should be output before the next non-whitespace `char`
+    pending_newline: bool,
+    /// Whether a non-whitespace `char` has been output on the current line
+    line_started: bool,
+}
+
+impl Normalizer {
+    /// Feeds `c` to the normalizer, passing the normalized `char`s (if any) to `out`.
+    pub(crate) fn push(&mut self, c: char, mut out: impl FnMut(char)) {
+        if is_line_terminator(c) {
+            if self.line_started {
+                self.pending_newline = true;
+            }
+            self.line_started = false;
+            self.pending_space = false;
+        } else if c.is_whitespace() {
+            if self.line_started {
+                self.pending_space = true;
+            }
+        } else {
+            if self.pending_newline {
+                out('\n');
+            } else if self.pending_space {
+                out(' ');
+            }
+            self.pending_newline = false;
+            self.pending_space = false;
+            self.line_started = true;
+            out(c);
+        }
+    }
+}
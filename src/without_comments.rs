@@ -1,46 +1,379 @@
+use crate::capture::Capture;
+use crate::error::Error;
+use crate::lines::StrippedLines;
+use crate::position::{is_line_terminator, Position};
+#[cfg(feature = "regex")]
+use crate::regex_pattern::RegexPattern;
+use crate::segments::Segments;
+use crate::validation::{validate, ValidationError};
+use std::borrow::Cow;
 use std::collections::VecDeque;
+use std::iter::FromIterator;
+use std::ops::Range;
 
 /// Buffer type used in the `WithoutComments` iterator, `Deref`s to `VecDeque<char>`.
-/// The capacity of the inner `VecDeque<char>` is constant, it represents the maximum length of
-/// buffer needed to match any open or close pattern for the current language.
+/// The maximum length of the buffer is constant, it is the length in `char`s of the longest
+/// open or close pattern for the current language, including the `char` following an open
+/// pattern if it needs to be checked, see `Comment::buf_len`. Only captured parts, and the close
+/// patterns built from them, and regular expressions make it hold more, up to
+/// `regex_pattern::MAX_LOOKAHEAD` `char`s for the latter.
 #[derive(Deref, Debug)]
-#[repr(transparent)]
-struct Buf(VecDeque<char>);
+struct Buf {
+    #[deref]
+    chars: VecDeque<char>,
+    /// Number of `char`s that the buffer holds when it's full
+    max_len: usize,
+    /// Position of the first element of the buffer in the input
+    front: Position,
+    /// Whether the first element of the buffer is at the start of a line
+    at_line_start: bool,
+    /// Whether the first element of the buffer is only preceded by whitespace on its line
+    after_indent: bool,
+    /// The `char` preceding the first element of the buffer, `None` at the start of the input
+    prev: Option<char>,
+}
+
+/// Length of a pattern in `char`s, which is what the buffer holds.
+fn char_len(pat: &str) -> usize {
+    pat.chars().count()
+}
+
+/// Alternative patterns, any one of which matches.
+pub(crate) type Patterns = Cow<'static, [Cow<'static, str>]>;
+
+/// The patterns ending a line comment: `\r\n`, `\n`, `\r` and the Unicode line and paragraph
+/// separators.
+pub(crate) const LINE_TERMINATORS: &[Cow<'static, str>] = &[
+    Cow::Borrowed("\r\n"),
+    Cow::Borrowed("\n"),
+    Cow::Borrowed("\r"),
+    Cow::Borrowed("\u{2028}"),
+    Cow::Borrowed("\u{2029}"),
+];
+
+/// Folds the case of a single `char` by going through its lowercase, then uppercase, then
+/// lowercase again, so that all of its cased forms (such as `σ`, `ς` and `Σ`, or `ß` and `ẞ`)
+/// fold to the same `char`s.
+fn fold_case(c: char) -> impl Iterator<Item = char> {
+    c.to_lowercase()
+        .flat_map(char::to_uppercase)
+        .flat_map(char::to_lowercase)
+}
+
+/// Whether two `char`s are the same once their case is folded.
+pub(crate) fn eq_ignore_case(a: char, b: char) -> bool {
+    a == b || fold_case(a).eq(fold_case(b))
+}
+
+/// Whether two patterns are the same once their case is folded, `char` by `char`.
+pub(crate) fn str_eq_ignore_case(a: &str, b: &str) -> bool {
+    char_len(a) == char_len(b) && a.chars().zip(b.chars()).all(|(a, b)| eq_ignore_case(a, b))
+}
+
+/// Where in a line a pattern can match.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Anchor {
+    /// Anywhere, this is the default
+    #[default]
+    Anywhere,
+    /// Only at the start of a line (column 1), such as Fortran's fixed-form `C` comments or
+    /// Ruby's `=begin` and `=end`
+    LineStart,
+    /// Only after indentation, that is, if nothing but whitespace precedes it on its line, such
+    /// as INI's `;` comments
+    Indented,
+    /// Only at the start of the input, such as a `#!` shebang line
+    FileStart,
+}
+
+impl Anchor {
+    /// Whether a pattern with this anchor matches everywhere that one with `other` does.
+    pub(crate) fn covers(self, other: Anchor) -> bool {
+        matches!(
+            (self, other),
+            (Anchor::Anywhere, _)
+                | (Anchor::Indented, Anchor::Indented)
+                | (Anchor::Indented, Anchor::LineStart)
+                | (Anchor::LineStart, Anchor::LineStart)
+                | (_, Anchor::FileStart)
+        )
+    }
+}
+
+/// A class of `char`s that must precede or follow an open pattern for it to match. The start
+/// and the end of the input belong to every class.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum CharClass {
+    /// Any `char`, this is the default
+    #[default]
+    Any,
+    /// Whitespace, including line terminators, such as before shell's `#`
+    Whitespace,
+    /// Anything but a word `char` (alphanumeric or `_`), making the pattern a whole word, such
+    /// as BASIC's `REM`
+    NonWord,
+}
+
+impl CharClass {
+    /// Whether `c` belongs to the class, `None` stands for the start or the end of the input.
+    pub(crate) fn matches(self, c: Option<char>) -> bool {
+        match (self, c) {
+            (CharClass::Any, _) | (_, None) => true,
+            (CharClass::Whitespace, Some(c)) => c.is_whitespace(),
+            (CharClass::NonWord, Some(c)) => !(c.is_alphanumeric() || c == '_'),
+        }
+    }
+
+    /// Whether every `char` in `other` is also in this class.
+    pub(crate) fn covers(self, other: CharClass) -> bool {
+        matches!(
+            (self, other),
+            (CharClass::Any, _)
+                | (CharClass::NonWord, CharClass::NonWord)
+                | (CharClass::NonWord, CharClass::Whitespace)
+                | (CharClass::Whitespace, CharClass::Whitespace)
+        )
+    }
+}
+
+/// Deserializes patterns from either a single string or a list of alternatives.
+#[cfg(feature = "serde")]
+fn deserialize_patterns<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Patterns, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let pats = match serde::Deserialize::deserialize(deserializer)? {
+        OneOrMany::One(pat) => vec![Cow::Owned(pat)],
+        OneOrMany::Many(pats) => pats.into_iter().map(Cow::Owned).collect(),
+    };
+    Ok(Cow::Owned(pats))
+}
 
 impl Buf {
     fn new(max_len: usize) -> Self {
-        Self(VecDeque::with_capacity(max_len))
+        Self {
+            chars: VecDeque::with_capacity(max_len),
+            max_len,
+            front: Position::start(),
+            at_line_start: true,
+            after_indent: true,
+            prev: None,
+        }
     }
 
     fn is_full(&self) -> bool {
-        self.len() == self.capacity()
+        // the buffer can hold more after matching a captured part or a regular expression
+        self.len() >= self.max_len
     }
 
-    /// Fill up inner `VecDeque<char>` to capacity from provided iterator. This is the only way
-    /// to add elements to the buffer.
+    /// Fill up inner `VecDeque<char>` to its maximum length from provided iterator, `fill_to`
+    /// makes it hold more.
     fn fill_up(&mut self, iter: &mut impl Iterator<Item = char>) {
         while !self.is_full() {
             match iter.next() {
                 None => break,
-                Some(x) => self.0.push_back(x),
+                Some(x) => self.chars.push_back(x),
             }
         }
     }
 
-    /// Checks whether the beginning of the buffer matches the provided pattern, the buffer should
-    /// be full when this method is called.
-    fn matches(&self, pat: &str) -> bool {
-        self.iter().take(pat.len()).copied().eq(pat.chars())
+    /// Checks whether the beginning of the buffer matches the provided pattern, optionally
+    /// ignoring case, the buffer should be full when this method is called.
+    fn matches(&self, pat: &str, ignore_case: bool) -> bool {
+        self.matches_at(0, pat, ignore_case)
     }
 
-    /// Assert that the buffer is not empty and pop the first element
-    fn pop_front(&mut self) -> char {
-        self.0.pop_front().unwrap()
+    /// Same as `matches` but starting `offset` `char`s into the buffer.
+    fn matches_at(&self, offset: usize, pat: &str, ignore_case: bool) -> bool {
+        self.len() >= offset + char_len(pat)
+            && self
+                .iter()
+                .skip(offset)
+                .zip(pat.chars())
+                .all(|(&c, p)| c == p || ignore_case && eq_ignore_case(c, p))
+    }
+
+    /// Matches the captured part of an opener and the pattern ending it, starting `offset`
+    /// `char`s into the buffer, returns their lengths in `char`s. The buffer is filled from
+    /// `iter` until it holds them and the `char` following them.
+    fn match_capture(
+        &mut self,
+        iter: &mut impl Iterator<Item = char>,
+        offset: usize,
+        capture: &Capture,
+        ignore_case: bool,
+    ) -> Option<(usize, usize)> {
+        let mut len = 0;
+        while len <= capture.max_len {
+            self.fill_to(iter, offset + len + 1);
+            match self.get(offset + len) {
+                Some(&c) if capture.chars.contains(c) => len += 1,
+                _ => break,
+            }
+        }
+        if len < capture.min_len || len > capture.max_len {
+            return None;
+        }
+        self.fill_to(iter, offset + len + char_len(&capture.end) + 1);
+        match self.matches_at(offset + len, &capture.end, ignore_case) {
+            true => Some((len, char_len(&capture.end))),
+            false => None,
+        }
+    }
+
+    /// Checks whether a pattern with the provided anchor can match at the front of the buffer.
+    fn is_at(&self, anchor: Anchor) -> bool {
+        match anchor {
+            Anchor::Anywhere => true,
+            Anchor::LineStart => self.at_line_start,
+            Anchor::Indented => self.after_indent,
+            Anchor::FileStart => self.prev.is_none(),
+        }
+    }
+
+    /// Length in `char`s of the longest open pattern of a comment that the beginning of the
+    /// buffer matches, if any, including its captured part, along with the range of the
+    /// captured part in the buffer. The buffer is filled from `iter` to hold the captured part.
+    fn longest_open(
+        &mut self,
+        iter: &mut impl Iterator<Item = char>,
+        comment: &Comment,
+    ) -> Option<(usize, Option<Range<usize>>)> {
+        if !self.is_at(comment.open_anchor) || !comment.preceded_by.matches(self.prev) {
+            return None;
+        }
+        let mut longest: Option<(usize, Option<Range<usize>>)> = None;
+        for pat in comment.open_pats.iter() {
+            if !self.matches(pat, comment.ignore_case) {
+                continue;
+            }
+            let len = char_len(pat);
+            let (len, captured) = match &comment.capture {
+                None => (len, None),
+                Some(capture) => {
+                    match self.match_capture(iter, len, capture, comment.ignore_case) {
+                        Some((captured, end)) => (len + captured + end, Some(len..len + captured)),
+                        None => continue,
+                    }
+                }
+            };
+            let longer = longest
+                .as_ref()
+                .map_or(true, |(longest, _)| len >= *longest);
+            if longer && comment.followed_by.matches(self.get(len).copied()) {
+                longest = Some((len, captured));
+            }
+        }
+        longest
+    }
+
+    /// Length in `char`s of the longest close pattern of a comment that the beginning of the
+    /// buffer matches, if any. For a comment with a capture, `captured_close` holds its close
+    /// patterns, built when it was opened.
+    fn longest_close(&self, comment: &Comment, captured_close: &[Cow<str>]) -> Option<usize> {
+        if !self.is_at(comment.close_anchor) {
+            return None;
+        }
+        let pats = match comment.capture {
+            Some(_) => captured_close,
+            None => &comment.close_pats,
+        };
+        pats.iter()
+            .filter(|pat| self.matches(pat, comment.ignore_case))
+            .map(|pat| char_len(pat))
+            .max()
+    }
+
+    /// Reads `char`s from `iter` until the buffer holds `len` of them or the input ends, the
+    /// buffer then holds more than its maximum length until enough `char`s are popped.
+    fn fill_to(&mut self, iter: &mut impl Iterator<Item = char>, len: usize) {
+        while self.len() < len {
+            match iter.next() {
+                None => break,
+                Some(x) => self.chars.push_back(x),
+            }
+        }
     }
 
-    /// Assert that the buffer has at least n elements and pop the first n elements
-    fn pop_front_n(&mut self, n: usize) {
-        let _ = self.0.drain(..n);
+    /// Length in `char`s of the match of a regular expression at the beginning of the buffer,
+    /// if any. The buffer is filled from `iter` for as long as the match could still change,
+    /// up to `MAX_LOOKAHEAD` `char`s.
+    #[cfg(feature = "regex")]
+    fn match_regex(
+        &mut self,
+        iter: &mut impl Iterator<Item = char>,
+        regex: &RegexPattern,
+    ) -> Option<usize> {
+        regex.match_len(self.prev, |index| {
+            self.fill_to(iter, index + 1);
+            self.get(index).copied()
+        })
+    }
+
+    /// Length in `char`s of the longest match of an open regular expression of a comment at the
+    /// beginning of the buffer, if any, see `longest_open`.
+    #[cfg(feature = "regex")]
+    fn longest_open_regex(
+        &mut self,
+        iter: &mut impl Iterator<Item = char>,
+        comment: &Comment,
+    ) -> Option<usize> {
+        if !self.is_at(comment.open_anchor) || !comment.preceded_by.matches(self.prev) {
+            return None;
+        }
+        let mut longest = None;
+        for regex in comment.open_regexes.iter() {
+            if let Some(len) = self.match_regex(iter, regex) {
+                if comment.followed_by.matches(self.get(len).copied()) {
+                    longest = longest.max(Some(len));
+                }
+            }
+        }
+        longest
+    }
+
+    /// Length in `char`s of the longest match of a close regular expression of a comment at the
+    /// beginning of the buffer, if any.
+    #[cfg(feature = "regex")]
+    fn longest_close_regex(
+        &mut self,
+        iter: &mut impl Iterator<Item = char>,
+        comment: &Comment,
+    ) -> Option<usize> {
+        if !self.is_at(comment.close_anchor) {
+            return None;
+        }
+        comment
+            .close_regexes
+            .iter()
+            .filter_map(|regex| self.match_regex(iter, regex))
+            .max()
+    }
+
+    /// Assert that the buffer is not empty and pop the first element
+    fn pop_front(&mut self) -> char {
+        let c = self.chars.pop_front().unwrap();
+        self.front.advance(self.prev, c);
+        self.at_line_start = is_line_terminator(c);
+        self.after_indent = self.at_line_start || self.after_indent && c.is_whitespace();
+        self.prev = Some(c);
+        c
     }
 }
 
@@ -67,27 +400,547 @@ impl<T> From<Option<T>> for Tription<T> {
 }
 
 /// Represents a set of rules for matching a specific comment in a language, for example 'block
-/// comment in rust' or 'line comment in haskell'.
-#[derive(Copy, Clone, Debug)]
+/// comment in rust' or 'line comment in haskell'. It can also represent a literal, such as a
+/// string, which is kept as text but inside of which no comment can start.
+///
+/// A comment can have several alternative open and close patterns, any open pattern can be
+/// closed by any close pattern.
+///
+/// With the `serde` feature, comments can be deserialized, for example from a configuration
+/// file, the flags default to `false`. Patterns can be given either as a single string
+/// (`open_pat = "//"`) or as a list of alternatives (`close_pats = ["\n", "\r"]`).
+///
+/// With the `regex` feature, a comment can also be opened and closed by regular expressions,
+/// see `Comment::open_regex`, given as `open_regex` and `close_regex` (or `open_regexes` and
+/// `close_regexes`), `open_pats` and `close_pats` can then be empty lists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
 pub struct Comment {
-    /// Open comment pattern, such as `/*`
-    pub(crate) open_pat: &'static str,
-    /// Close comment pattern, such as `*/`
-    pub(crate) close_pat: &'static str,
+    /// Open comment patterns, such as `/*`
+    #[cfg_attr(
+        feature = "serde",
+        serde(alias = "open_pat", deserialize_with = "deserialize_patterns")
+    )]
+    pub(crate) open_pats: Patterns,
+    /// Close comment patterns, such as `*/`
+    #[cfg_attr(
+        feature = "serde",
+        serde(alias = "close_pat", deserialize_with = "deserialize_patterns")
+    )]
+    pub(crate) close_pats: Patterns,
     /// Whether this type of comment can be nested. For example, rust block comments can be
     /// nested while C block comments can't.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) nests: bool,
     /// Whether to return the close comment pattern. For example, in rust block comments `*/`
     /// isn't returned while in rust line comments, `\n` is returned.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) keep_close_pat: bool, // whether to still return close_pat as part of the text
     /// Whether to allow the close comment pattern in regular text. For example, in rust `*/`
     /// will panic unless it closes a block comment while `\n` will be treated normally.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) allow_close_pat: bool, // whether to allow close_pat without matching open_pat
+    /// Whether this is a literal rather than a comment. Literals, including their open and
+    /// close patterns, are returned as text, `keep_close_pat` and `allow_close_pat` don't apply
+    /// to them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) literal: bool,
+    /// Character escaping the `char` that follows it, inside of a literal, such as `\` in
+    /// `"a \" b"`, or in regular text for a comment, such as `\` in LaTeX's `\%`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) escape: Option<char>,
+    /// Priority of the open pattern when several match at the same point, higher priorities
+    /// win, then longer patterns, then the comment declared first
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) priority: i32,
+    /// Where in a line the open patterns can match
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) open_anchor: Anchor,
+    /// Where in a line the close patterns can match
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) close_anchor: Anchor,
+    /// Class of the `char` that must precede an open pattern
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) preceded_by: CharClass,
+    /// Class of the `char` that must follow an open pattern
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) followed_by: CharClass,
+    /// Whether the open and close patterns match regardless of case, such as `REM` and `rem`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) ignore_case: bool,
+    /// Variable part captured after the open pattern, which the close pattern has to repeat
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) capture: Option<Capture>,
+    /// Regular expressions that open the comment, as alternatives to its open patterns
+    #[cfg(feature = "regex")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            alias = "open_regex",
+            deserialize_with = "crate::regex_pattern::deserialize_regexes"
+        )
+    )]
+    pub(crate) open_regexes: Vec<RegexPattern>,
+    /// Regular expressions that close the comment, as alternatives to its close patterns
+    #[cfg(feature = "regex")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            alias = "close_regex",
+            deserialize_with = "crate::regex_pattern::deserialize_regexes"
+        )
+    )]
+    pub(crate) close_regexes: Vec<RegexPattern>,
+}
+
+impl Comment {
+    /// A line comment starting with `open_pat` and ending at the end of the line, the line
+    /// terminator (`\n`, `\r\n`, `\r`, `U+2028` or `U+2029`) itself is kept. This is the same as
+    /// the line comments of the built-in languages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::line(";")].into_boxed_slice();
+    /// let without_comments = "a ; b\r\nc ; d\re"
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "a \r\nc \re");
+    /// ```
+    pub fn line(open_pat: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            open_pats: Cow::Owned(vec![open_pat.into()]),
+            close_pats: Cow::Borrowed(LINE_TERMINATORS),
+            nests: false,
+            keep_close_pat: true,
+            allow_close_pat: true,
+            literal: false,
+            escape: None,
+            priority: 0,
+            open_anchor: Anchor::Anywhere,
+            close_anchor: Anchor::Anywhere,
+            preceded_by: CharClass::Any,
+            followed_by: CharClass::Any,
+            ignore_case: false,
+            capture: None,
+            #[cfg(feature = "regex")]
+            open_regexes: Vec::new(),
+            #[cfg(feature = "regex")]
+            close_regexes: Vec::new(),
+        }
+    }
+
+    /// A block comment delimited by `open_pat` and `close_pat`. By default, it doesn't nest,
+    /// `close_pat` is removed along with the comment and an unmatched `close_pat` causes a
+    /// panic, use the other methods to change this.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::block("(*", "*)").nests(true)].into_boxed_slice();
+    /// let without_comments = "a (* (* b *) *) c"
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "a  c");
+    /// ```
+    pub fn block(
+        open_pat: impl Into<Cow<'static, str>>,
+        close_pat: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            open_pats: Cow::Owned(vec![open_pat.into()]),
+            close_pats: Cow::Owned(vec![close_pat.into()]),
+            nests: false,
+            keep_close_pat: false,
+            allow_close_pat: false,
+            literal: false,
+            escape: None,
+            priority: 0,
+            open_anchor: Anchor::Anywhere,
+            close_anchor: Anchor::Anywhere,
+            preceded_by: CharClass::Any,
+            followed_by: CharClass::Any,
+            ignore_case: false,
+            capture: None,
+            #[cfg(feature = "regex")]
+            open_regexes: Vec::new(),
+            #[cfg(feature = "regex")]
+            close_regexes: Vec::new(),
+        }
+    }
+
+    /// A literal (such as a string) delimited by `open_pat` and `close_pat`, it is kept as is
+    /// and comment patterns inside of it are ignored. By default, it has no escape character,
+    /// use `escape` to set one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::line("#"), Comment::literal("\"", "\"").escape('\\')]
+    ///     .into_boxed_slice();
+    /// let without_comments = r##"a = "# \" #" # b"##
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, r##"a = "# \" #" "##);
+    /// ```
+    pub fn literal(
+        open_pat: impl Into<Cow<'static, str>>,
+        close_pat: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            open_pats: Cow::Owned(vec![open_pat.into()]),
+            close_pats: Cow::Owned(vec![close_pat.into()]),
+            nests: false,
+            keep_close_pat: false,
+            allow_close_pat: false,
+            literal: true,
+            escape: None,
+            priority: 0,
+            open_anchor: Anchor::Anywhere,
+            close_anchor: Anchor::Anywhere,
+            preceded_by: CharClass::Any,
+            followed_by: CharClass::Any,
+            ignore_case: false,
+            capture: None,
+            #[cfg(feature = "regex")]
+            open_regexes: Vec::new(),
+            #[cfg(feature = "regex")]
+            close_regexes: Vec::new(),
+        }
+    }
+
+    /// Sets whether this type of comment can be nested.
+    pub fn nests(mut self, nests: bool) -> Self {
+        self.nests = nests;
+        self
+    }
+
+    /// Sets whether the close pattern is returned as part of the text.
+    pub fn keep_close_pat(mut self, keep_close_pat: bool) -> Self {
+        self.keep_close_pat = keep_close_pat;
+        self
+    }
+
+    /// Sets whether the close pattern is allowed in regular text.
+    pub fn allow_close_pat(mut self, allow_close_pat: bool) -> Self {
+        self.allow_close_pat = allow_close_pat;
+        self
+    }
+
+    /// Adds an alternative open pattern.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::line(";").alt_open_pat("#")].into_boxed_slice();
+    /// let without_comments = "a ; b\nc # d\n"
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "a \nc \n");
+    /// ```
+    pub fn alt_open_pat(mut self, open_pat: impl Into<Cow<'static, str>>) -> Self {
+        self.open_pats.to_mut().push(open_pat.into());
+        self
+    }
+
+    /// Adds an alternative close pattern, when several close patterns match, the longest one
+    /// closes the comment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::block("<!--", "-->").alt_close_pat("--!>")].into_boxed_slice();
+    /// let without_comments = "a <!-- b --!> c"
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "a  c");
+    /// ```
+    pub fn alt_close_pat(mut self, close_pat: impl Into<Cow<'static, str>>) -> Self {
+        self.close_pats.to_mut().push(close_pat.into());
+        self
+    }
+
+    /// Sets the priority of the open pattern, by default, when several open patterns match,
+    /// the longest one wins. A higher priority wins over a longer pattern, this is only needed
+    /// when a shorter pattern must win.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    /// let strip = |language: Vec<Comment>| {
+    ///     "a /// b\nc".chars().without_comments(language.into_boxed_slice()).collect::<String>()
+    /// };
+    /// // the longest pattern wins regardless of the order
+    /// let doc = Comment::line("///").keep_close_pat(false);
+    /// assert_eq!(strip(vec![Comment::line("//"), doc.clone()]), "a c");
+    /// assert_eq!(strip(vec![Comment::line("//").priority(1), doc]), "a \nc");
+    /// ```
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets where in a line the open patterns can match, by default, anywhere.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Anchor, Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::line(";").open_anchor(Anchor::Indented)].into_boxed_slice();
+    /// let without_comments = "  ; a\nb = c ; d\n"
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "  \nb = c ; d\n");
+    /// ```
+    pub fn open_anchor(mut self, anchor: Anchor) -> Self {
+        self.open_anchor = anchor;
+        self
+    }
+
+    /// Sets where in a line the close patterns can match, by default, anywhere. A comment is
+    /// never open at the start of the input, so `Anchor::FileStart` is rejected by `validate`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Anchor, Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::block("=begin", "=end")
+    ///     .open_anchor(Anchor::LineStart)
+    ///     .close_anchor(Anchor::LineStart)]
+    /// .into_boxed_slice();
+    /// let without_comments = "a\n=begin\nb =end\n=end\nc"
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "a\n\nc");
+    /// ```
+    pub fn close_anchor(mut self, anchor: Anchor) -> Self {
+        self.close_anchor = anchor;
+        self
+    }
+
+    /// Sets the class of the `char` that must precede an open pattern, by default, any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{CharClass, Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::line("#").preceded_by(CharClass::Whitespace)];
+    /// let without_comments = "echo $# a#b # c\n"
+    ///     .chars()
+    ///     .without_comments(language.into_boxed_slice())
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "echo $# a#b \n");
+    /// ```
+    pub fn preceded_by(mut self, class: CharClass) -> Self {
+        self.preceded_by = class;
+        self
+    }
+
+    /// Sets the class of the `char` that must follow an open pattern, by default, any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{CharClass, Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::line("REM")
+    ///     .preceded_by(CharClass::NonWord)
+    ///     .followed_by(CharClass::NonWord)]
+    /// .into_boxed_slice();
+    /// let without_comments = "10 REMARK = 1: REM note\n"
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "10 REMARK = 1: \n");
+    /// ```
+    pub fn followed_by(mut self, class: CharClass) -> Self {
+        self.followed_by = class;
+        self
+    }
+
+    /// Sets whether the open and close patterns match regardless of case. Case is folded with
+    /// Unicode rules rather than ASCII only, one `char` at a time, so `ẞ` matches `ß` but not
+    /// `ss`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::block("<Σχόλιο>", "</Σχόλιο>").ignore_case(true)];
+    /// let without_comments = "a <σχόλιο> b </ΣΧΌΛΙΟ> c"
+    ///     .chars()
+    ///     .without_comments(language.into_boxed_slice())
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "a  c");
+    /// ```
+    pub fn ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    /// Sets a variable part captured after the open pattern, which the close pattern has to
+    /// repeat, see `Capture`. A comment with a capture can't nest and its close patterns are
+    /// always allowed in regular text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Capture, Comment, IntoWithoutComments as _};
+    /// let raw_string = Comment::literal("R\"", ")")
+    ///     .capture(Capture::none_of(" ()\\\t\n", "(").close_suffix("\""));
+    /// let language = vec![Comment::line("//"), raw_string].into_boxed_slice();
+    /// let without_comments = r#"s = R"x(// )" )x"; // c"#
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, r#"s = R"x(// )" )x"; "#);
+    /// ```
+    pub fn capture(mut self, capture: Capture) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// A block comment opened by the regular expression `open` and closed by `close`, with the
+    /// same defaults as `Comment::block`. The other methods apply as well, open patterns and
+    /// regular expressions can be mixed with `alt_open_pat` and `open_regex`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _, RegexPattern};
+    /// let language = vec![Comment::regex(
+    ///     RegexPattern::new(r"<<([A-Z]+)>>").unwrap(),
+    ///     RegexPattern::new(r"<</[A-Z]+>>").unwrap(),
+    /// )];
+    /// let without_comments = "a <<NOTE>> b <</NOTE>> c"
+    ///     .chars()
+    ///     .without_comments(language.into_boxed_slice())
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "a  c");
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn regex(open: RegexPattern, close: RegexPattern) -> Self {
+        Self {
+            open_pats: Cow::Borrowed(&[]),
+            close_pats: Cow::Borrowed(&[]),
+            open_regexes: vec![open],
+            close_regexes: vec![close],
+            ..Self::block("", "")
+        }
+    }
+
+    /// Adds a regular expression opening the comment, as an alternative to its open patterns.
+    /// It only matches at the current position, and when several open patterns or regular
+    /// expressions match, the longest match wins. Anchors, char classes and priorities apply
+    /// to it, but `ignore_case` and `capture` don't, use `(?i)` and the close regular
+    /// expressions instead. To keep memory bounded, the buffer grows to at most
+    /// `regex_pattern::MAX_LOOKAHEAD` `char`s to find the match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Anchor, Comment, IntoWithoutComments as _, RegexPattern};
+    /// // assembler comments: `;` anywhere, `*` or `C` followed by a space in the first column
+    /// let language = vec![
+    ///     Comment::line(";"),
+    ///     Comment::line("*")
+    ///         .open_regex(RegexPattern::new(r"C\s").unwrap())
+    ///         .open_anchor(Anchor::LineStart),
+    /// ];
+    /// let without_comments = "* a\nC b\nCLR r1 ; c\n"
+    ///     .chars()
+    ///     .without_comments(language.into_boxed_slice())
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "\n\nCLR r1 \n");
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn open_regex(mut self, open: RegexPattern) -> Self {
+        self.open_regexes.push(open);
+        self
+    }
+
+    /// Adds a regular expression closing the comment, as an alternative to its close patterns,
+    /// see `open_regex`.
+    #[cfg(feature = "regex")]
+    pub fn close_regex(mut self, close: RegexPattern) -> Self {
+        self.close_regexes.push(close);
+        self
+    }
+
+    /// The first open pattern, or regular expression, shown in errors.
+    fn first_open_pat(&self) -> Cow<'static, str> {
+        #[cfg(feature = "regex")]
+        if self.open_pats.is_empty() {
+            if let Some(open) = self.open_regexes.first() {
+                return Cow::Owned(open.as_str().to_string());
+            }
+        }
+        self.open_pats.first().cloned().unwrap_or_default()
+    }
+
+    /// Number of `char`s that the buffer needs to hold to match the patterns of this comment, it
+    /// grows as needed for captured parts and regular expressions.
+    fn buf_len(&self) -> usize {
+        let longest = |pats: &[Cow<str>]| pats.iter().map(|pat| char_len(pat)).max().unwrap_or(0);
+        let mut open = longest(&self.open_pats);
+        let close = longest(&self.close_pats);
+        if self.followed_by != CharClass::Any {
+            // room for the `char` following the open pattern
+            open += 1;
+        }
+        #[cfg(feature = "regex")]
+        if !self.open_regexes.is_empty() || !self.close_regexes.is_empty() {
+            // the buffer grows as needed to match regular expressions
+            open = open.max(1);
+        }
+        open.max(close)
+    }
+
+    /// Sets the escape character. Inside of a literal, the `char` following it never closes the
+    /// literal. For a comment, it applies to regular text, where the `char` following it is
+    /// returned as text along with it, so an escaped open pattern doesn't start a comment. An
+    /// escape character also escapes itself, so an escaped escape character doesn't escape the
+    /// `char` that follows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    /// let language = vec![Comment::line("%").escape('\\')].into_boxed_slice();
+    /// let without_comments = "50\\% off % sale\n\\\\% note"
+    ///     .chars()
+    ///     .without_comments(language)
+    ///     .collect::<String>();
+    /// assert_eq!(&without_comments, "50\\% off \n\\\\");
+    /// ```
+    pub fn escape(mut self, escape: char) -> Self {
+        self.escape = Some(escape);
+        self
+    }
 }
 
 /// `char` iterator that removes comments based on a list of `Comment` specifications.
 /// Unclosed comments (`//...` or `/*...` or equivalents) continue until the end of the iterator.
-/// Closing unopened block comments (`... */` or equivalent) causes a panic.
+/// Closing unopened block comments (`... */` or equivalent) causes a panic, use `try_next` to
+/// get an `Error` instead.
 pub struct WithoutComments<I: Iterator<Item = char>> {
     /// Inner `char` iterator
     iter: I,
@@ -101,6 +954,36 @@ pub struct WithoutComments<I: Iterator<Item = char>> {
     /// comment doesn't nest and Some(d) otherwise, where d is the current nesting depth
     /// starting at 0.
     state: Option<(usize, Option<usize>)>,
+    /// Number of `char`s at the front of the buffer that belong to an already matched open or
+    /// close pattern (or to an escape sequence), they are returned without any further matching.
+    pending: usize,
+    /// Class of the next pending `char`, a `CommentStart` is followed by `Comment`s.
+    pending_class: Class,
+    /// Close patterns of the current comment if it has a capture, built from the captured part
+    captured_close: Vec<Cow<'static, str>>,
+}
+
+/// Classification of a single `char` of the input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Class {
+    /// Regular text, returned by the `WithoutComments` iterator
+    Text,
+    /// The first `char` of a (top-level) comment
+    CommentStart,
+    /// Any other `char` of a comment, including the close pattern
+    Comment,
+    /// A `char` of a literal, including its open and close patterns, returned as text
+    Literal,
+}
+
+impl Class {
+    /// Whether the `char` is returned by the `WithoutComments` iterator.
+    pub(crate) fn is_text(self) -> bool {
+        match self {
+            Class::Text | Class::Literal => true,
+            Class::CommentStart | Class::Comment => false,
+        }
+    }
 }
 
 impl<I: Iterator<Item = char>> WithoutComments<I> {
@@ -111,6 +994,85 @@ impl<I: Iterator<Item = char>> WithoutComments<I> {
             buf: Buf::new(buf_len),
             comments,
             state: None,
+            pending: 0,
+            pending_class: Class::Comment,
+            captured_close: Vec::new(),
+        }
+    }
+
+    /// The inner iterator.
+    pub(crate) fn inner_mut(&mut self) -> &mut I {
+        &mut self.iter
+    }
+
+    /// Position of the next `char` to be read from the inner iterator, after a call to `next`
+    /// that returned `None`, this is the end of the input.
+    pub fn position(&self) -> Position {
+        self.buf.front
+    }
+
+    /// Fallible equivalent of `Iterator::next`, returns an error instead of panicking when a
+    /// close pattern that isn't allowed in regular text is found outside of a comment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{languages, Error, IntoWithoutComments as _};
+    /// let mut iter = "a */".chars().without_comments(languages::rust());
+    /// assert_eq!(iter.try_next(), Ok(Some('a')));
+    /// assert_eq!(iter.try_next(), Ok(Some(' ')));
+    /// match iter.try_next() {
+    ///     Err(Error::UnmatchedClose { position, .. }) => assert_eq!(position.column, 3),
+    ///     other => panic!("unexpected {:?}", other),
+    /// }
+    /// ```
+    pub fn try_next(&mut self) -> Result<Option<char>, Error> {
+        loop {
+            match self.next_classified()? {
+                None => return Ok(None),
+                Some((c, _, class)) if class.is_text() => return Ok(Some(c)),
+                Some(_) => (),
+            }
+        }
+    }
+
+    /// Returns the next `char` of the input (including the ones that are part of comments)
+    /// along with its position and classification.
+    pub(crate) fn next_classified(&mut self) -> Result<Option<(char, Position, Class)>, Error> {
+        loop {
+            match self.next_()? {
+                Tription::None => return Ok(None),
+                Tription::Some(item) => return Ok(Some(item)),
+                Tription::Wait => (),
+            }
+        }
+    }
+
+    /// Fallible equivalent of `Iterator::collect`, stops at the first error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{languages, IntoWithoutComments as _};
+    /// let ok = "a /* b */".chars().without_comments(languages::c()).try_collect::<String>();
+    /// assert_eq!(ok.unwrap(), "a ");
+    /// let err = "a */ b".chars().without_comments(languages::c()).try_collect::<String>();
+    /// assert!(err.is_err());
+    /// ```
+    pub fn try_collect<B: FromIterator<char>>(mut self) -> Result<B, Error> {
+        let mut error = None;
+        let collected = std::iter::from_fn(|| match self.try_next() {
+            Ok(c) => c,
+            Err(err) => {
+                error = Some(err);
+                None
+            }
+        })
+        .collect();
+
+        match error {
+            Some(err) => Err(err),
+            None => Ok(collected),
         }
     }
 
@@ -118,31 +1080,68 @@ impl<I: Iterator<Item = char>> WithoutComments<I> {
     /// This is for the case where a block comment follows right after another
     /// (`/* ... *//* ... */` or equivalent), after reading `*/`, the buffer needs to be filled
     /// to make sure that any eventual `/*` will be matched, this is done in the next call to
-    /// `next_`, thus, the calling loop in `Iterator::next` is told to wait one more iteration.
-    fn next_(&mut self) -> Tription<char> {
+    /// `next_`, thus, the calling loop in `next_classified` is told to wait one more iteration.
+    fn next_(&mut self) -> Result<Tription<(char, Position, Class)>, Error> {
         // at least one element missing from previous call
         self.buf.fill_up(&mut self.iter);
 
         if self.buf.is_empty() {
-            return Tription::None;
+            return Ok(Tription::None);
+        }
+
+        let position = self.buf.front;
+
+        // still inside an already matched pattern
+        if self.pending > 0 {
+            self.pending -= 1;
+            let class = self.pending_class;
+            if class == Class::CommentStart {
+                self.pending_class = Class::Comment;
+            }
+            return Ok(Tription::Some((self.buf.pop_front(), position, class)));
         }
 
         // if in comment
         if let Some((idx, ref mut nesting)) = self.state {
             let comment = &self.comments[idx];
-            let &Comment {
-                open_pat,
-                close_pat,
+            let Comment {
                 keep_close_pat,
+                literal,
+                escape,
                 ..
             } = comment;
 
+            if comment.capture.is_some() {
+                // the close patterns built from the captured part can be longer than the buffer
+                let len = self.captured_close.iter().map(|pat| char_len(pat)).max();
+                self.buf.fill_to(&mut self.iter, len.unwrap_or(0));
+            }
+            let close = self.buf.longest_close(comment, &self.captured_close);
+            #[cfg(feature = "regex")]
+            let close = close.max(self.buf.longest_close_regex(&mut self.iter, comment));
+
+            if *literal {
+                if escape.is_some() && self.buf.front() == escape.as_ref() {
+                    // the escape character and the escaped char
+                    self.pending = 2;
+                } else if let Some(len) = close {
+                    self.pending = len;
+                    self.state = None;
+                } else {
+                    let c = self.buf.pop_front();
+                    return Ok(Tription::Some((c, position, Class::Literal)));
+                }
+                self.pending_class = Class::Literal;
+                return Ok(Tription::Wait);
+            }
+
             // check close before open to make thinks like python's '''...''' work
-            if self.buf.matches(close_pat) {
+            if let Some(len) = close {
                 // matched close pattern
 
-                if !keep_close_pat {
-                    self.buf.pop_front_n(close_pat.len());
+                if !*keep_close_pat {
+                    self.pending = len;
+                    self.pending_class = Class::Comment;
                 }
 
                 match nesting {
@@ -152,49 +1151,135 @@ impl<I: Iterator<Item = char>> WithoutComments<I> {
                     Some(d) => *d -= 1,
                 }
             } else if let Some(depth) = nesting {
-                if self.buf.matches(open_pat) {
+                let open = self
+                    .buf
+                    .longest_open(&mut self.iter, comment)
+                    .map(|(len, _)| len);
+                #[cfg(feature = "regex")]
+                let open = open.max(self.buf.longest_open_regex(&mut self.iter, comment));
+                if let Some(len) = open {
                     // matched nesting open pattern
-                    self.buf.pop_front_n(open_pat.len());
+                    self.pending = len;
+                    self.pending_class = Class::Comment;
                     *depth += 1;
                 } else {
-                    self.buf.pop_front();
+                    let c = self.buf.pop_front();
+                    return Ok(Tription::Some((c, position, Class::Comment)));
                 }
             } else {
-                self.buf.pop_front();
+                let c = self.buf.pop_front();
+                return Ok(Tription::Some((c, position, Class::Comment)));
             }
 
-            Tription::Wait
+            Ok(Tription::Wait)
         } else {
             // if in text
-            // #![feature(bindings_after_at)]
-            // for comment @ Comment(open_pat, .., nests) in self.comments.as_ref() {
-            // for each rule...
+            // an escape character of a comment and the escaped char are text
+            let escaped = self.comments.iter().any(|comment| {
+                !comment.literal
+                    && comment.escape.is_some()
+                    && self.buf.front() == comment.escape.as_ref()
+            });
+            if escaped {
+                self.pending = 2;
+                self.pending_class = Class::Text;
+                return Ok(Tription::Wait);
+            }
+
+            // the matching open pattern with the highest priority wins, then the longest one,
+            // then the first one declared
+            let mut best: Option<(usize, &Comment, usize, Option<Range<usize>>)> = None;
             for (idx, comment) in self.comments.iter().enumerate() {
-                let Comment {
-                    open_pat,
-                    close_pat,
-                    nests,
-                    allow_close_pat,
-                    ..
-                } = comment;
-
-                // if it matches open pattern, open
-                if self.buf.matches(open_pat) {
-                    self.buf.pop_front_n(open_pat.len());
-
-                    let nesting = match nests {
-                        true => Some(0),
-                        false => None,
-                    };
-                    self.state = Some((idx, nesting));
-                    return Tription::Wait;
-                } else if !allow_close_pat && self.buf.matches(close_pat) {
-                    // if close pattern forbidden, panic
-                    panic!("Got \"{}\" without matching \"{}\"", close_pat, open_pat)
+                let open = self.buf.longest_open(&mut self.iter, comment);
+                // a longer match of a regular expression wins, it has no captured part
+                #[cfg(feature = "regex")]
+                let open = match (open, self.buf.longest_open_regex(&mut self.iter, comment)) {
+                    (Some((len, _)), Some(regex_len)) if regex_len > len => Some((regex_len, None)),
+                    (None, Some(regex_len)) => Some((regex_len, None)),
+                    (open, _) => open,
+                };
+                let (len, captured) = match open {
+                    Some(open) => open,
+                    None => continue,
+                };
+                let better = match best {
+                    None => true,
+                    Some((_, best, best_len, _)) => {
+                        (comment.priority, len) > (best.priority, best_len)
+                    }
+                };
+                if better {
+                    best = Some((idx, comment, len, captured));
                 }
             }
 
-            Tription::Some(self.buf.pop_front())
+            // if it matches an open pattern, open
+            if let Some((idx, comment, len, captured)) = best {
+                if let (Some(capture), Some(captured)) = (&comment.capture, captured) {
+                    let captured = self
+                        .buf
+                        .iter()
+                        .skip(captured.start)
+                        .take(captured.len())
+                        .collect::<String>();
+                    self.captured_close = comment
+                        .close_pats
+                        .iter()
+                        .map(|pat| format!("{}{}{}", pat, captured, capture.close_suffix).into())
+                        .collect();
+                }
+
+                self.pending = len;
+                self.pending_class = match comment.literal {
+                    true => Class::Literal,
+                    false => Class::CommentStart,
+                };
+
+                // comments with a capture can't nest
+                let nesting = match comment.nests && comment.capture.is_none() {
+                    true => Some(0),
+                    false => None,
+                };
+                self.state = Some((idx, nesting));
+                return Ok(Tription::Wait);
+            }
+
+            // if a close pattern is forbidden, fail
+            for comment in self.comments.iter() {
+                if comment.literal
+                    || comment.allow_close_pat
+                    || comment.capture.is_some()
+                    || !self.buf.is_at(comment.close_anchor)
+                {
+                    continue;
+                }
+                let close_pat = comment
+                    .close_pats
+                    .iter()
+                    .find(|pat| self.buf.matches(pat, comment.ignore_case))
+                    .cloned();
+                #[cfg(feature = "regex")]
+                let close_pat = match close_pat {
+                    Some(close_pat) => Some(close_pat),
+                    None => self
+                        .buf
+                        .longest_close_regex(&mut self.iter, comment)
+                        .map(|len| Cow::Owned(self.buf.iter().take(len).collect())),
+                };
+                if let Some(close_pat) = close_pat {
+                    return Err(Error::UnmatchedClose {
+                        close_pat,
+                        open_pat: comment.first_open_pat(),
+                        position,
+                    });
+                }
+            }
+
+            Ok(Tription::Some((
+                self.buf.pop_front(),
+                position,
+                Class::Text,
+            )))
         }
     }
 }
@@ -202,16 +1287,11 @@ impl<I: Iterator<Item = char>> WithoutComments<I> {
 impl<I: Iterator<Item = char>> Iterator for WithoutComments<I> {
     type Item = char;
 
-    /// Simply calls `WithoutComments::next_`, a return value of `Tription::Wait` signifies
-    /// that another attempt should be made, `Tription::Some` and `Tription::None` are
-    /// equivalent to the same variants of the `Option` type.
+    /// Simply calls `WithoutComments::try_next`, panicking on errors.
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match self.next_() {
-                Tription::None => return None,
-                Tription::Some(c) => return Some(c),
-                Tription::Wait => (),
-            }
+        match self.try_next() {
+            Ok(c) => c,
+            Err(err) => panic!("{}", err),
         }
     }
 }
@@ -226,7 +1306,7 @@ where
     /// # Arguments
     ///
     /// * `language` - A boxed slice containing all the comments that the returned iterator
-    /// will be removing
+    ///   will be removing
     ///
     /// # Example
     ///
@@ -240,23 +1320,60 @@ where
     /// assert_eq!(&without_comments, "Sbeve");
     /// ```
     fn without_comments(self, language: Box<[Comment]>) -> WithoutComments<Self> {
-        let mut buf_len = 0;
-        for &Comment {
-            open_pat,
-            close_pat,
-            ..
-        } in language.iter()
-        {
-            if open_pat.len() > buf_len {
-                buf_len = open_pat.len()
-            }
-            if close_pat.len() > buf_len {
-                buf_len = close_pat.len()
-            }
-        }
+        let buf_len = language.iter().map(Comment::buf_len).max().unwrap_or(0);
         assert_ne!(buf_len, 0);
         WithoutComments::new(self, language, buf_len)
     }
+
+    /// Same as `without_comments` but checks the comment specifications with
+    /// `validation::validate` first, returning an error instead of misbehaving if they're
+    /// invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::validation::ValidationError;
+    /// use no_comment::{Comment, IntoWithoutComments as _};
+    ///
+    /// let language = vec![Comment::line("//").priority(1), Comment::line("///")];
+    /// match "a /// b".chars().try_without_comments(language.into_boxed_slice()) {
+    ///     Err(ValidationError::ShadowedOpenPattern { index: 1, by: 0 }) => (),
+    ///     _ => panic!("`///` can never match"),
+    /// }
+    /// ```
+    fn try_without_comments(
+        self,
+        language: Box<[Comment]>,
+    ) -> Result<WithoutComments<Self>, ValidationError> {
+        validate(&language)?;
+        Ok(self.without_comments(language))
+    }
+
+    /// Returns a `Segments` iterator splitting self into text and comments, the text segments
+    /// contain exactly what `without_comments` would return.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use no_comment::{languages, IntoWithoutComments as _};
+    /// let comments = "a /* b */ c // d"
+    ///     .chars()
+    ///     .segments(languages::rust())
+    ///     .map(Result::unwrap)
+    ///     .filter(|segment| segment.is_comment())
+    ///     .map(|segment| (segment.start.column, segment.text))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(comments, [(3, "/* b */".to_string()), (13, "// d".to_string())]);
+    /// ```
+    fn segments(self, language: Box<[Comment]>) -> Segments<Self> {
+        Segments::new(self.without_comments(language))
+    }
+
+    /// Returns a `StrippedLines` iterator over the lines of self without comments, along with
+    /// their original line numbers.
+    fn stripped_lines(self, language: Box<[Comment]>) -> StrippedLines<Self> {
+        StrippedLines::new(self.without_comments(language))
+    }
 }
 
 /// Blanket implementation